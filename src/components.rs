@@ -1,26 +1,108 @@
 use bevy::prelude::*;
-use bevy_inspector_egui::Inspectable;
+use bevy_inspector_egui::{egui, Context, Inspectable};
 
 pub mod person;
 pub mod player;
 pub mod camera;
-
-#[derive(Component)]
-pub struct AnimationTimer(pub Timer);
+pub mod animation;
+pub mod ai;
+pub mod combat;
+pub mod enemy;
+pub mod projectile;
+pub mod stats;
+pub mod item;
+pub mod inventory;
+pub mod status;
+pub mod hud;
+pub mod main_menu;
 
 #[derive(Inspectable, Component)]
 pub struct Health {
     pub(crate) hp: f32,
+    pub(crate) max_hp: f32,
     pub(crate) armor: f32,
 }
 
+impl Health {
+    pub fn new(hp: f32, armor: f32) -> Self {
+        Self { hp, max_hp: hp, armor }
+    }
+
+    // how much hp is left, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        if self.max_hp > 0.0 { self.hp / self.max_hp } else { 0.0 }
+    }
+
+    /*
+    Armor, plus any bonus armor (from a shield), takes a flat amount off every hit,
+    but a hit always does at least 1 damage
+    Returns the damage that actually got through
+     */
+    pub fn take_damage(&mut self, damage: f32, bonus_armor: f32) -> f32 {
+        let amount = (damage - self.armor - bonus_armor).max(1.0).min(self.hp);
+        self.hp -= amount;
+        amount
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.hp <= 0.0
+    }
+}
+
 /*
+Spent by running and dodging, comes back on its own once it hasn't been used for regen_delay seconds
+ */
 #[derive(Inspectable, Component)]
-pub struct CircleCollider {
-    pub(crate) radius: f32,
-    pub layer: u32,
+pub struct Stamina {
+    pub(crate) current: f32,
+    pub(crate) max: f32,
+    // per second
+    pub(crate) regen: f32,
+    pub(crate) regen_delay: f32,
+    pub(crate) since_spent: f32,
+}
+
+impl Stamina {
+    pub fn new(max: f32, regen: f32, regen_delay: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen,
+            regen_delay,
+            since_spent: regen_delay,
+        }
+    }
+
+    /*
+    Spend amount if there is enough of it, otherwise nothing is spent
+     */
+    pub fn try_spend(&mut self, amount: f32) -> bool {
+        if self.current < amount {
+            return false;
+        }
+        self.current -= amount;
+        self.since_spent = 0.0;
+        true
+    }
+
+    /*
+    Spend as much of amount as there is, for things that use stamina over time
+     */
+    pub fn drain(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+        self.since_spent = 0.0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current <= 0.0
+    }
 }
-*/
+
+/*
+Entity can't be damaged until the timer runs out, then the component is removed
+ */
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
 
 #[derive(Inspectable)]
 pub enum ColliderType {
@@ -29,6 +111,7 @@ pub enum ColliderType {
 }
 
 impl PartialEq for ColliderType {
+    #[allow(clippy::match_like_matches_macro)]
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (ColliderType::Trigger, ColliderType::Trigger) => true,
@@ -38,40 +121,249 @@ impl PartialEq for ColliderType {
     }
 }
 
+/*
+Named collision layers, each one is a bit in a LayerMask
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CollisionLayer {
+    Player,
+    Enemy,
+    Wall,
+    Projectile,
+    Pickup,
+    Trigger,
+}
+
+impl CollisionLayer {
+    pub const ALL: [CollisionLayer; 6] = [
+        CollisionLayer::Player,
+        CollisionLayer::Enemy,
+        CollisionLayer::Wall,
+        CollisionLayer::Projectile,
+        CollisionLayer::Pickup,
+        CollisionLayer::Trigger,
+    ];
+
+    pub const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LayerMask(pub u32);
+
+impl LayerMask {
+    pub const ALL: LayerMask = LayerMask(u32::MAX);
+
+    pub const fn from_layers(layers: &[CollisionLayer]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < layers.len() {
+            bits |= layers[i].bit();
+            i += 1;
+        }
+        LayerMask(bits)
+    }
+
+    pub fn contains(self, layer: CollisionLayer) -> bool {
+        self.0 & layer.bit() != 0
+    }
+
+    pub fn intersects(self, other: LayerMask) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+// shown as one checkbox per named layer
+impl Inspectable for LayerMask {
+    type Attributes = ();
+
+    fn ui(&mut self, ui: &mut egui::Ui, _options: Self::Attributes, _context: &mut Context) -> bool {
+        let mut changed = false;
+        ui.vertical(|ui| {
+            for layer in CollisionLayer::ALL {
+                let mut enabled = self.contains(layer);
+                if ui.checkbox(&mut enabled, format!("{:?}", layer)).changed() {
+                    self.0 ^= layer.bit();
+                    changed = true;
+                }
+            }
+        });
+        changed
+    }
+}
+
 #[derive(Inspectable, Component)]
 pub struct BoxCollider {
     pub(crate) width: f32,
     pub(crate) height: f32,
-    pub layer: u32,
+    // the layers this collider is on
+    pub membership: LayerMask,
+    // the layers this collider wants to collide with
+    pub collision_mask: LayerMask,
     pub offset: Vec2,
     pub scale: Vec2,
     pub collider_type: ColliderType,
 }
 
+/*
+Round collider, centered on the transform plus the offset
+ */
+#[derive(Inspectable, Component)]
+pub struct CircleCollider {
+    pub(crate) radius: f32,
+    pub membership: LayerMask,
+    pub collision_mask: LayerMask,
+    pub offset: Vec2,
+    pub collider_type: ColliderType,
+}
+
+/*
+A collider placed in the world, what the narrow phase actually tests
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum ColliderShape {
+    Box { min: Vec2, max: Vec2 },
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl ColliderShape {
+    /*
+    Get the min and max corners of the box around the shape
+     */
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        match *self {
+            ColliderShape::Box { min, max } => (min, max),
+            ColliderShape::Circle { center, radius } => (center - Vec2::splat(radius), center + Vec2::splat(radius)),
+        }
+    }
+
+    pub fn overlaps(&self, other: &ColliderShape) -> bool {
+        match (*self, *other) {
+            (ColliderShape::Box { min, max }, ColliderShape::Box { min: other_min, max: other_max }) => {
+                min.x < other_max.x &&
+                    max.x > other_min.x &&
+                    min.y < other_max.y &&
+                    max.y > other_min.y
+            }
+            (ColliderShape::Circle { center, radius }, ColliderShape::Circle { center: other_center, radius: other_radius }) => {
+                center.distance_squared(other_center) < (radius + other_radius) * (radius + other_radius)
+            }
+            (ColliderShape::Circle { center, radius }, ColliderShape::Box { min, max })
+            | (ColliderShape::Box { min, max }, ColliderShape::Circle { center, radius }) => {
+                // closest point of the box to the circle
+                let closest = center.clamp(min, max);
+                center.distance_squared(closest) < radius * radius
+            }
+        }
+    }
+}
+
+/*
+Everything the collision systems need from a collider, whatever its shape
+ */
+pub trait Collider {
+    fn membership(&self) -> LayerMask;
+    fn collision_mask(&self) -> LayerMask;
+    fn collider_type(&self) -> &ColliderType;
+    fn shape(&self, transform: &Transform) -> ColliderShape;
+
+    /*
+    Two colliders only interact if each one is on a layer the other collides with
+     */
+    fn interacts_with(&self, other: &dyn Collider) -> bool {
+        self.collision_mask().intersects(other.membership()) && other.collision_mask().intersects(self.membership())
+    }
+
+    /*
+    Whether other stops this collider from moving through it
+     */
+    fn is_blocked_by(&self, other: &dyn Collider) -> bool {
+        self.interacts_with(other) && *other.collider_type() == ColliderType::Solid
+    }
+}
+
 impl BoxCollider {
-    pub fn collides_with(&self, other: &BoxCollider, transform: &Transform, other_transform: &Transform) -> bool {
-        if self.layer != other.layer {
-            return false;
+    /*
+    Get the min and max corners of the collider in world space
+    The box is centered on the transform (like the sprites are) plus the offset
+     */
+    pub fn bounds(&self, transform: &Transform) -> (Vec2, Vec2) {
+        let center = transform.translation.truncate() + self.offset;
+        let half_size = Vec2::new(self.width * self.scale.x, self.height * self.scale.y) / 2.0;
+        (center - half_size, center + half_size)
+    }
+}
+
+impl Collider for BoxCollider {
+    fn membership(&self) -> LayerMask {
+        self.membership
+    }
+
+    fn collision_mask(&self) -> LayerMask {
+        self.collision_mask
+    }
+
+    fn collider_type(&self) -> &ColliderType {
+        &self.collider_type
+    }
+
+    fn shape(&self, transform: &Transform) -> ColliderShape {
+        let (min, max) = self.bounds(transform);
+        ColliderShape::Box { min, max }
+    }
+}
+
+impl Collider for CircleCollider {
+    fn membership(&self) -> LayerMask {
+        self.membership
+    }
+
+    fn collision_mask(&self) -> LayerMask {
+        self.collision_mask
+    }
+
+    fn collider_type(&self) -> &ColliderType {
+        &self.collider_type
+    }
+
+    fn shape(&self, transform: &Transform) -> ColliderShape {
+        ColliderShape::Circle {
+            center: transform.translation.truncate() + self.offset,
+            radius: self.radius,
         }
+    }
+}
 
-        let x = transform.translation.x + self.offset.x;
-        let y = transform.translation.y + self.offset.y;
-        let other_x = other_transform.translation.x + other.offset.x;
-        let other_y = other_transform.translation.y + other.offset.y;
-        let width = self.width * self.scale.x;
-        let height = self.height * self.scale.y;
-        let other_width = other.width * other.scale.x;
-        let other_height = other.height * other.scale.y;
-        x < other_x + other_width &&
-            x + width > other_x &&
-            y < other_y + other_height &&
-            y + height > other_y
+/*
+Pick whichever collider an entity has, for queries that take either shape
+ */
+pub fn as_collider<'a>(box_collider: Option<&'a BoxCollider>, circle_collider: Option<&'a CircleCollider>) -> Option<&'a dyn Collider> {
+    match (box_collider, circle_collider) {
+        (Some(box_collider), _) => Some(box_collider),
+        (None, Some(circle_collider)) => Some(circle_collider),
+        (None, None) => None,
     }
 }
 
-#[derive(Component, Inspectable)]
+#[derive(Component)]
 pub struct Collision {
-    pub(crate) collisions: Vec<u32>,
+    pub(crate) collisions: Vec<Entity>,
+}
+
+// entities can't be made up in the inspector, so the list is shown read only
+impl Inspectable for Collision {
+    type Attributes = ();
+
+    fn ui(&mut self, ui: &mut egui::Ui, _options: Self::Attributes, _context: &mut Context) -> bool {
+        if self.collisions.is_empty() {
+            ui.label("No collisions");
+        }
+        for entity in &self.collisions {
+            ui.label(format!("{:?}", entity));
+        }
+        false
+    }
 }
 
 impl FromWorld for Collision {
@@ -84,6 +376,7 @@ impl FromWorld for Collision {
 }
 
 impl Collision {
+    #[allow(dead_code, clippy::len_zero)]
     pub fn is_colliding(&self) -> bool {
         self.collisions.len() > 0
     }
@@ -98,7 +391,39 @@ pub struct Unknown {
     pub sprite: SpriteBundle
 }
 
+#[derive(Component)]
+pub struct Wall;
+
+/*
+Root of something that belongs to the floor being played, like walls, enemies and pickups
+ */
+#[derive(Component)]
+pub struct FloorEntity;
+
+/*
+Root of something that belongs to the game being played, like the player and the HUD
+ */
+#[derive(Component)]
+pub struct GameEntity;
+
+/*
+The way down, drawn over the middle of the room farthest from the start, see spawn_stairs
+ */
+#[derive(Component)]
+pub struct Stairs;
+
+/*
+A static collider covering a rectangle of wall tiles
+ */
+#[derive(Bundle)]
+pub struct WallBundle {
+    pub wall: Wall,
+    pub collider: BoxCollider,
+    #[bundle]
+    pub transform: TransformBundle,
+}
 
+#[allow(dead_code)]
 #[derive(Bundle)]
 pub struct WorldTile {
     #[bundle]
@@ -107,3 +432,102 @@ pub struct WorldTile {
 
 #[derive(Component)]
 pub struct LoadingText;
+
+/*
+Map of the whole floor shown while the player can see it, and the dot marking the player on it
+ */
+#[derive(Component)]
+pub struct MapOverlay;
+
+#[derive(Component)]
+pub struct MapMarker;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn box_collider(width: f32, height: f32, offset: Vec2, scale: Vec2) -> BoxCollider {
+        BoxCollider {
+            width,
+            height,
+            membership: LayerMask::ALL,
+            collision_mask: LayerMask::ALL,
+            offset,
+            scale,
+            collider_type: ColliderType::Solid,
+        }
+    }
+
+    fn circle_collider(radius: f32, offset: Vec2) -> CircleCollider {
+        CircleCollider {
+            radius,
+            membership: LayerMask::ALL,
+            collision_mask: LayerMask::ALL,
+            offset,
+            collider_type: ColliderType::Solid,
+        }
+    }
+
+    fn at(x: f32, y: f32) -> Transform {
+        Transform::from_xyz(x, y, 0.0)
+    }
+
+    // 10x10 box centered on the origin
+    fn unit_box() -> ColliderShape {
+        box_collider(10.0, 10.0, Vec2::ZERO, Vec2::ONE).shape(&at(0.0, 0.0))
+    }
+
+    #[test]
+    fn box_box() {
+        let other = |x: f32, y: f32| box_collider(10.0, 10.0, Vec2::ZERO, Vec2::ONE).shape(&at(x, y));
+
+        assert!(unit_box().overlaps(&other(5.0, 5.0)));
+        // edges touching is not an overlap
+        assert!(!unit_box().overlaps(&other(10.0, 0.0)));
+        assert!(!unit_box().overlaps(&other(0.0, -10.0)));
+        assert!(!unit_box().overlaps(&other(20.0, 0.0)));
+        assert!(!unit_box().overlaps(&other(0.0, 11.0)));
+    }
+
+    #[test]
+    fn circle_circle() {
+        let circle = |x: f32, y: f32| circle_collider(5.0, Vec2::ZERO).shape(&at(x, y));
+
+        assert!(circle(0.0, 0.0).overlaps(&circle(6.0, 0.0)));
+        assert!(!circle(0.0, 0.0).overlaps(&circle(10.0, 0.0)));
+        assert!(!circle(0.0, 0.0).overlaps(&circle(8.0, 8.0)));
+        assert!(!circle(0.0, 0.0).overlaps(&circle(30.0, 0.0)));
+    }
+
+    #[test]
+    fn circle_box() {
+        let circle = |x: f32, y: f32| circle_collider(5.0, Vec2::ZERO).shape(&at(x, y));
+
+        assert!(circle(8.0, 0.0).overlaps(&unit_box()));
+        assert!(unit_box().overlaps(&circle(8.0, 0.0)));
+        // touching the side
+        assert!(!circle(10.0, 0.0).overlaps(&unit_box()));
+        // the corner is further away than the sides, the bounding boxes overlap but the shapes don't
+        assert!(!circle(9.0, 9.0).overlaps(&unit_box()));
+        assert!(!circle(20.0, 0.0).overlaps(&unit_box()));
+    }
+
+    #[test]
+    fn offset_and_scale() {
+        // the offset moves the box onto the other one
+        let moved = box_collider(10.0, 10.0, Vec2::new(12.0, 0.0), Vec2::ONE).shape(&at(0.0, 0.0));
+        let other = box_collider(10.0, 10.0, Vec2::ZERO, Vec2::ONE).shape(&at(20.0, 0.0));
+        assert!(moved.overlaps(&other));
+        assert!(!unit_box().overlaps(&other));
+
+        // scaled up twice as wide it reaches 10 out
+        let scaled = box_collider(10.0, 10.0, Vec2::ZERO, Vec2::new(2.0, 1.0)).shape(&at(0.0, 0.0));
+        assert_eq!(scaled.bounds(), (Vec2::new(-10.0, -5.0), Vec2::new(10.0, 5.0)));
+        assert!(scaled.overlaps(&circle_collider(5.0, Vec2::ZERO).shape(&at(14.0, 0.0))));
+        assert!(!scaled.overlaps(&circle_collider(5.0, Vec2::ZERO).shape(&at(0.0, 10.0))));
+
+        // the circle's offset counts the same way
+        let circle = circle_collider(5.0, Vec2::new(0.0, -20.0)).shape(&at(0.0, 20.0));
+        assert!(circle.overlaps(&unit_box()));
+    }
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+
+#[derive(Inspectable, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AiState {
+    Idle,
+    Wander,
+    Chase,
+    Attack,
+    Flee,
+    Dead,
+}
+
+/*
+How one kind of enemy behaves, see EnemyKind::ai_tuning
+ */
+#[derive(Inspectable, Clone, Copy, Debug)]
+pub struct AiTuning {
+    // world units
+    pub sight_range: f32,
+    pub attack_range: f32,
+    // units per second
+    pub speed: f32,
+    // runs away when hp drops to this fraction of max hp, 0 never runs
+    pub flee_health: f32,
+    // chance to go for a wander each time it gets bored of standing around, 0 stays put
+    pub wander_chance: f32,
+    // how far from where it stands it will wander, in tiles
+    pub wander_range: i32,
+}
+
+/*
+Enemy brain, update_ai moves it between states and the other enemy systems act on the state
+ */
+#[derive(Inspectable, Component)]
+pub struct Ai {
+    pub state: AiState,
+    pub tuning: AiTuning,
+    // where it is headed while wandering, or where it last saw the player while chasing
+    pub target: Option<Vec2>,
+    // which way update_ai wants to go this frame, move_enemies does the moving
+    pub(crate) move_direction: Vec2,
+    // time left before it gets bored of idling or wandering
+    pub(crate) state_time: f32,
+}
+
+impl Ai {
+    pub fn new(tuning: AiTuning) -> Self {
+        Self {
+            state: AiState::Idle,
+            tuning,
+            target: None,
+            move_direction: Vec2::ZERO,
+            state_time: 0.0,
+        }
+    }
+}
+
+/*
+Tiles an enemy is following to get to where it is going, see follow_paths
+ */
+#[derive(Component)]
+pub struct CachedPath {
+    // world positions of the tiles still to walk through, in order
+    pub waypoints: Vec<Vec2>,
+    // tile the path leads to
+    pub goal: IVec2,
+    // a moving goal only gets a new path once this runs out
+    pub(crate) repath: Timer,
+}
+
+impl CachedPath {
+    pub fn new(repath_time: f32) -> Self {
+        let mut path = Self {
+            waypoints: Vec::new(),
+            goal: IVec2::ZERO,
+            repath: Timer::from_seconds(repath_time, false),
+        };
+        path.clear();
+        path
+    }
+
+    /*
+    Drop the path, the next one can be worked out straight away
+     */
+    pub fn clear(&mut self) {
+        self.waypoints.clear();
+        let duration = self.repath.duration();
+        // finishes on the next tick
+        self.repath.set_elapsed(duration);
+    }
+}
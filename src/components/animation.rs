@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+/*
+Plays clips from the AnimationLibrary on a TextureAtlasSprite
+set picks the group of clips (the player, a monster kind), clip is the one playing right now
+ */
+#[derive(Component)]
+pub struct Animator {
+    pub set: String,
+    pub clip: String,
+    // frame within the clip, not the atlas index
+    pub(crate) frame: usize,
+    pub(crate) timer: Timer,
+    pub(crate) finished: bool,
+    // the clip has not been applied to the sprite yet
+    pub(crate) restarted: bool,
+}
+
+impl Animator {
+    pub fn new(set: &str, clip: &str) -> Self {
+        Self {
+            set: set.to_string(),
+            clip: clip.to_string(),
+            frame: 0,
+            timer: Timer::from_seconds(1.0, true),
+            finished: false,
+            restarted: true,
+        }
+    }
+
+    /*
+    Switch to another clip, starting it from its first frame
+    Playing the clip that is already playing does nothing, so this can be called every frame
+     */
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.clip = clip.to_string();
+            self.restart();
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.finished = false;
+        self.restarted = true;
+    }
+}
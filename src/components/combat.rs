@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+
+/*
+Lets an entity swing a melee attack, the swing lasts as long as the attack animation
+and a new one can't start until the cooldown is over
+ */
+#[derive(Component)]
+pub struct MeleeAttack {
+    pub damage: f32,
+    pub knockback: f32,
+    // how far in front of the entity the hitbox is centered
+    pub reach: f32,
+    pub hitbox_size: Vec2,
+    pub(crate) swing: Timer,
+    pub(crate) cooldown: Timer,
+}
+
+impl MeleeAttack {
+    pub fn new(damage: f32, knockback: f32, swing_time: f32, cooldown: f32) -> Self {
+        let mut swing = Timer::from_seconds(swing_time, false);
+        let mut cooldown = Timer::from_seconds(cooldown, false);
+        // ready to swing straight away
+        swing.tick(swing.duration());
+        cooldown.tick(cooldown.duration());
+
+        Self {
+            damage,
+            knockback,
+            reach: 20.0,
+            hitbox_size: Vec2::new(24.0, 28.0),
+            swing,
+            cooldown,
+        }
+    }
+
+    pub fn is_swinging(&self) -> bool {
+        !self.swing.finished()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+
+    pub fn start(&mut self) {
+        self.swing.reset();
+        self.cooldown.reset();
+    }
+}
+
+/*
+Short lived trigger that hurts whatever it overlaps, once per target
+owner is never hurt by its own hitbox
+ */
+#[derive(Component)]
+pub struct Hitbox {
+    pub owner: Entity,
+    pub damage: f32,
+    pub knockback: f32,
+    pub(crate) hit: Vec<Entity>,
+    pub(crate) lifetime: Timer,
+}
+
+impl Hitbox {
+    pub fn new(owner: Entity, damage: f32, knockback: f32, lifetime: f32) -> Self {
+        Self {
+            owner,
+            damage,
+            knockback,
+            hit: Vec::new(),
+            lifetime: Timer::from_seconds(lifetime, false),
+        }
+    }
+}
+
+/*
+Entity is being pushed back by a hit, the push slows down to nothing over the timer
+ */
+#[derive(Inspectable, Component)]
+pub struct Knockback {
+    pub velocity: Vec2,
+    pub(crate) time_left: f32,
+    pub(crate) duration: f32,
+}
+
+impl Knockback {
+    pub fn new(velocity: Vec2, duration: f32) -> Self {
+        Self {
+            velocity,
+            time_left: duration,
+            duration,
+        }
+    }
+}
+
+/*
+Blocks hits coming from the front while raised
+A blocked hit loses block of its damage (1 negates it) and then has to get through
+the shield's armor on top of the entity's own
+ */
+#[derive(Inspectable, Component)]
+pub struct Shield {
+    pub block: f32,
+    pub armor: f32,
+    // hits within this many degrees either side of where the entity faces are blocked
+    pub arc: f32,
+    pub raised: bool,
+}
+
+impl Shield {
+    pub fn new(block: f32, armor: f32, arc: f32) -> Self {
+        Self {
+            block,
+            armor,
+            arc,
+            raised: false,
+        }
+    }
+
+    /*
+    Whether a hit coming from direction (pointing from the entity to the attacker)
+    lands on the shield of an entity facing facing
+     */
+    pub fn covers(&self, facing: Vec2, direction: Vec2) -> bool {
+        self.raised && direction != Vec2::ZERO && facing.angle_between(direction).abs() <= self.arc.to_radians()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raised(arc: f32) -> Shield {
+        Shield {
+            raised: true,
+            ..Shield::new(0.5, 1.0, arc)
+        }
+    }
+
+    #[test]
+    fn covers_hits_from_the_front() {
+        let shield = raised(60.0);
+
+        assert!(shield.covers(Vec2::X, Vec2::X));
+        assert!(shield.covers(Vec2::X, Vec2::new(1.0, 1.0)));
+        assert!(shield.covers(Vec2::X, Vec2::new(1.0, -1.0)));
+        // facing doesn't have to be normalized
+        assert!(shield.covers(Vec2::new(-3.0, 0.0), Vec2::new(-1.0, 0.5)));
+    }
+
+    #[test]
+    fn misses_hits_outside_the_arc() {
+        let shield = raised(60.0);
+
+        assert!(!shield.covers(Vec2::X, Vec2::NEG_X));
+        assert!(!shield.covers(Vec2::X, Vec2::Y));
+        assert!(!shield.covers(Vec2::X, Vec2::new(1.0, -2.0)));
+        assert!(raised(90.0).covers(Vec2::X, Vec2::new(0.01, -1.0)));
+    }
+
+    #[test]
+    fn only_covers_while_raised() {
+        let mut shield = Shield::new(0.5, 1.0, 180.0);
+        assert!(!shield.covers(Vec2::X, Vec2::X));
+
+        shield.raised = true;
+        assert!(shield.covers(Vec2::X, Vec2::X));
+        // a hit from right on top of the entity has no direction to block
+        assert!(!shield.covers(Vec2::X, Vec2::ZERO));
+    }
+}
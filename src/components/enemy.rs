@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+use serde::{Deserialize, Serialize};
+
+use crate::components::ai::{Ai, AiTuning, CachedPath};
+use crate::components::animation::Animator;
+use crate::components::combat::{MeleeAttack, Shield};
+use crate::components::projectile::{ProjectileKind, RangedAttack};
+use crate::components::status::StatusEffects;
+use crate::components::{BoxCollider, CircleCollider, ColliderType, Collision, CollisionLayer, Health, LayerMask};
+use crate::resources::enemy_stats::EnemyStats;
+
+// what enemies bump into, the Trigger layer lets the player's hitboxes reach them
+pub const ENEMY_COLLISION_MASK: LayerMask = LayerMask::from_layers(&[
+    CollisionLayer::Player,
+    CollisionLayer::Enemy,
+    CollisionLayer::Wall,
+    CollisionLayer::Projectile,
+    CollisionLayer::Trigger,
+]);
+
+#[derive(Component)]
+pub struct Enemy;
+
+/*
+The monsters from the bundled sprite sheets
+ */
+#[derive(Inspectable, Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum EnemyKind {
+    Goblin,
+    Skeleton,
+    Mushroom,
+    FlyingEye,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 4] = [
+        EnemyKind::Goblin,
+        EnemyKind::Skeleton,
+        EnemyKind::Mushroom,
+        EnemyKind::FlyingEye,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyKind::Goblin => "Goblin",
+            EnemyKind::Skeleton => "Skeleton",
+            EnemyKind::Mushroom => "Mushroom",
+            EnemyKind::FlyingEye => "Flying eye",
+        }
+    }
+
+    // the group of clips in animations.anim.ron
+    pub fn animation_set(&self) -> &'static str {
+        match self {
+            EnemyKind::Goblin => "goblin",
+            EnemyKind::Skeleton => "skeleton",
+            EnemyKind::Mushroom => "mushroom",
+            EnemyKind::FlyingEye => "flying_eye",
+        }
+    }
+
+    // the Flying eye never lands, so it has no idle clip
+    pub fn idle_clip(&self) -> &'static str {
+        match self {
+            EnemyKind::FlyingEye => "flight",
+            _ => "idle",
+        }
+    }
+
+    /*
+    Goblins are quick and cowardly, Skeletons are slow and never back down,
+    Mushrooms sit still until something comes close and the Flying eye roams and sees far
+    The speed is that of a level 1 enemy, the rest of its stats are in enemies.stats.ron
+     */
+    pub fn ai_tuning(&self) -> AiTuning {
+        match self {
+            EnemyKind::Goblin => AiTuning {
+                sight_range: 200.0,
+                attack_range: 28.0,
+                speed: 80.0,
+                flee_health: 0.25,
+                wander_chance: 0.5,
+                wander_range: 4,
+            },
+            EnemyKind::Skeleton => AiTuning {
+                sight_range: 160.0,
+                attack_range: 30.0,
+                speed: 45.0,
+                flee_health: 0.0,
+                wander_chance: 0.3,
+                wander_range: 3,
+            },
+            EnemyKind::Mushroom => AiTuning {
+                sight_range: 110.0,
+                attack_range: 26.0,
+                speed: 35.0,
+                flee_health: 0.0,
+                wander_chance: 0.0,
+                wander_range: 0,
+            },
+            EnemyKind::FlyingEye => AiTuning {
+                sight_range: 260.0,
+                attack_range: 28.0,
+                speed: 70.0,
+                flee_health: 0.15,
+                wander_chance: 0.8,
+                wander_range: 6,
+            },
+        }
+    }
+
+    /*
+    Knockback, swing time (how long the attack clip runs) and cooldown of the melee attack,
+    the damage comes from the enemy's stats
+     */
+    pub fn melee_attack(&self, damage: f32) -> MeleeAttack {
+        match self {
+            EnemyKind::Goblin => MeleeAttack::new(damage, 120.0, 8.0 / 14.0, 1.2),
+            EnemyKind::Skeleton => MeleeAttack::new(damage, 160.0, 8.0 / 14.0, 1.6),
+            EnemyKind::Mushroom => MeleeAttack::new(damage, 100.0, 8.0 / 14.0, 1.5),
+            EnemyKind::FlyingEye => MeleeAttack::new(damage, 80.0, 8.0 / 14.0, 1.0),
+        }
+    }
+
+    /*
+    Goblins throw bombs, Mushrooms puff spores and the Flying eye spits bolts
+    Projectile, range, swing time (how long the attack3 clip runs) and cooldown
+     */
+    pub fn ranged_attack(&self) -> Option<RangedAttack> {
+        match self {
+            EnemyKind::Goblin => Some(RangedAttack::new(ProjectileKind::Bomb, 150.0, 12.0 / 14.0, 3.0)),
+            EnemyKind::Skeleton => None,
+            EnemyKind::Mushroom => Some(RangedAttack::new(ProjectileKind::Spore, 100.0, 11.0 / 14.0, 2.0)),
+            EnemyKind::FlyingEye => Some(RangedAttack::new(ProjectileKind::EyeBolt, 180.0, 6.0 / 10.0, 1.8)),
+        }
+    }
+
+    // only the Skeleton carries a shield, it's big enough to stop most of a hit
+    pub fn shield(&self) -> Option<Shield> {
+        match self {
+            EnemyKind::Skeleton => Some(Shield::new(0.8, 3.0, 70.0)),
+            _ => None,
+        }
+    }
+
+    // the clip it plays while moving around
+    pub fn move_clip(&self) -> &'static str {
+        match self {
+            EnemyKind::Skeleton => "walk",
+            EnemyKind::FlyingEye => "flight",
+            _ => "run",
+        }
+    }
+
+    // size of the body in world units, the 150x150 frames are mostly empty space
+    pub fn body_size(&self) -> Vec2 {
+        match self {
+            EnemyKind::Goblin => Vec2::new(18.0, 22.0),
+            EnemyKind::Skeleton => Vec2::new(18.0, 28.0),
+            EnemyKind::Mushroom => Vec2::new(18.0, 22.0),
+            EnemyKind::FlyingEye => Vec2::new(20.0, 20.0),
+        }
+    }
+
+    /*
+    Collider around the body, scaled up with the rank like the sprite is
+    The eye is round so it gets a circle, everything else is a box
+     */
+    pub fn collider(&self, scale: f32) -> EnemyCollider {
+        let size = self.body_size();
+        let membership = LayerMask::from_layers(&[CollisionLayer::Enemy]);
+
+        match self {
+            EnemyKind::FlyingEye => EnemyCollider::Circle(CircleCollider {
+                radius: size.x * scale / 2.0,
+                membership,
+                collision_mask: ENEMY_COLLISION_MASK,
+                offset: Vec2::ZERO,
+                collider_type: ColliderType::Solid,
+            }),
+            _ => EnemyCollider::Box(BoxCollider {
+                width: size.x,
+                height: size.y,
+                membership,
+                collision_mask: ENEMY_COLLISION_MASK,
+                offset: Vec2::ZERO,
+                scale: Vec2::splat(scale),
+                collider_type: ColliderType::Solid,
+            }),
+        }
+    }
+}
+
+/*
+Either kind of collider an enemy can have, see EnemyKind::collider
+ */
+pub enum EnemyCollider {
+    Box(BoxCollider),
+    Circle(CircleCollider),
+}
+
+/*
+Elites and Champions are tougher, bigger and tinted versions of a monster
+ */
+#[derive(Inspectable, Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum EnemyRank {
+    Normal,
+    Elite,
+    Champion,
+}
+
+impl EnemyRank {
+    // rarest first, the order they are rolled in
+    pub const RARE: [EnemyRank; 2] = [EnemyRank::Champion, EnemyRank::Elite];
+
+    // goes in front of the monster's name
+    pub fn title(&self) -> Option<&'static str> {
+        match self {
+            EnemyRank::Normal => None,
+            EnemyRank::Elite => Some("Elite"),
+            EnemyRank::Champion => Some("Champion"),
+        }
+    }
+}
+
+/*
+Level and rank an enemy was spawned at, with what they make it worth
+ */
+#[derive(Inspectable, Component)]
+pub struct EnemyLevel {
+    pub level: u32,
+    pub rank: EnemyRank,
+    // projectiles it fires have their damage multiplied by this
+    pub damage_multiplier: f32,
+    // for whoever kills it
+    pub xp: u32,
+}
+
+#[derive(Bundle)]
+pub struct EnemyBundle {
+    pub enemy: Enemy,
+    pub kind: EnemyKind,
+    pub level: EnemyLevel,
+    pub health: Health,
+    pub name: crate::components::person::Name,
+    pub animator: Animator,
+    pub ai: Ai,
+    pub path: CachedPath,
+    pub attack: MeleeAttack,
+    pub effects: StatusEffects,
+    #[bundle]
+    pub sprite: SpriteSheetBundle,
+    // the collider isn't part of it, its type depends on the kind, see EnemyKind::collider
+    pub collision: Collision,
+}
+
+impl EnemyBundle {
+    /*
+    Enemy of kind with stats, the transform's scale is multiplied by the size of its rank
+     */
+    pub fn new(kind: EnemyKind, stats: &EnemyStats, texture_atlas: Handle<TextureAtlas>, transform: Transform) -> Self {
+        let tuning = kind.ai_tuning();
+        let name = match stats.rank.title() {
+            Some(title) => format!("{} {}", title, kind.name()),
+            None => kind.name().to_string(),
+        };
+
+        Self {
+            enemy: Enemy,
+            kind,
+            level: EnemyLevel {
+                level: stats.level,
+                rank: stats.rank,
+                damage_multiplier: stats.damage_multiplier,
+                xp: stats.xp,
+            },
+            health: Health::new(stats.health, stats.armor),
+            name: crate::components::person::Name(name),
+            animator: Animator::new(kind.animation_set(), kind.idle_clip()),
+            ai: Ai::new(AiTuning {
+                speed: tuning.speed * stats.speed_multiplier,
+                ..tuning
+            }),
+            path: CachedPath::new(0.5),
+            attack: kind.melee_attack(stats.damage),
+            effects: StatusEffects::default(),
+            sprite: SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    color: stats.tint,
+                    ..Default::default()
+                },
+                texture_atlas,
+                transform: transform.with_scale(transform.scale * stats.scale),
+                ..Default::default()
+            },
+            collision: Collision {
+                collisions: Vec::new(),
+            },
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+/*
+Markers for the parts of the HUD that change, see spawn_hud
+ */
+
+#[derive(Component)]
+pub struct Hud;
+
+// the red part of the health bar, as wide as the fraction of hp left
+#[derive(Component)]
+pub struct HealthFill;
+
+#[derive(Component)]
+pub struct HealthText;
+
+#[derive(Component)]
+pub struct ArmorText;
+
+// the part of the xp bar already earned towards the next level
+#[derive(Component)]
+pub struct XpFill;
+
+#[derive(Component)]
+pub struct XpText;
+
+#[derive(Component)]
+pub struct LevelText;
+
+#[derive(Component)]
+pub struct FloorText;
+
+// holds one line for every effect the player is under
+#[derive(Component)]
+pub struct StatusStrip;
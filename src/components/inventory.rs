@@ -0,0 +1,288 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::resources::items::{EquipSlot, ItemDatabase, ItemDef, ItemModifiers};
+
+/*
+Some number of one item taking up one inventory slot
+ */
+#[derive(Clone, Debug)]
+pub struct ItemStack {
+    // id in items.items.ron
+    pub item: String,
+    pub count: u32,
+}
+
+/*
+The items being carried, up to capacity stacks, and the ones being worn
+Equipped items don't take up room in the inventory
+ */
+#[derive(Component)]
+pub struct Inventory {
+    pub stacks: Vec<ItemStack>,
+    pub capacity: usize,
+    pub equipped: HashMap<EquipSlot, String>,
+}
+
+impl Inventory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            stacks: Vec::new(),
+            capacity,
+            equipped: HashMap::default(),
+        }
+    }
+
+    /*
+    Add count of item, topping up stacks that aren't full before starting new ones
+    Returns how many didn't fit
+     */
+    pub fn add(&mut self, item: &ItemDef, mut count: u32) -> u32 {
+        let max_stack = item.max_stack.max(1);
+
+        for stack in self.stacks.iter_mut().filter(|stack| stack.item == item.id) {
+            let added = count.min(max_stack.saturating_sub(stack.count));
+            stack.count += added;
+            count -= added;
+        }
+        while count > 0 && self.stacks.len() < self.capacity {
+            let added = count.min(max_stack);
+            self.stacks.push(ItemStack {
+                item: item.id.clone(),
+                count: added,
+            });
+            count -= added;
+        }
+
+        count
+    }
+
+    // whether one more of item would fit, on top of a stack or in a slot of its own
+    fn has_room_for(&self, item: &ItemDef) -> bool {
+        self.stacks.len() < self.capacity
+            || self.stacks.iter().any(|stack| stack.item == item.id && stack.count < item.max_stack.max(1))
+    }
+
+    /*
+    Take one item off the stack at index, the stack goes away when it's empty
+    Returns the id of the item taken
+     */
+    pub fn take(&mut self, index: usize) -> Option<String> {
+        let stack = self.stacks.get_mut(index)?;
+        stack.count -= 1;
+        let item = stack.item.clone();
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        Some(item)
+    }
+
+    /*
+    Wear the item at index, whatever was in its slot goes back into the inventory
+    Does nothing for items that can't be worn, or when there's no room for the one taken off
+     */
+    pub fn equip(&mut self, index: usize, items: &ItemDatabase) -> bool {
+        let (stack, slot) = match self.stacks.get(index).and_then(|stack| Some((stack, items.get(&stack.item)?.slot?))) {
+            Some(found) => found,
+            None => return false,
+        };
+
+        // the last item of a stack leaves its slot free for the one taken off
+        let previous = self.equipped.get(&slot).and_then(|id| items.get(id));
+        if let Some(previous) = previous {
+            if stack.count > 1 && !self.has_room_for(previous) {
+                return false;
+            }
+        }
+
+        let item = match self.take(index) {
+            Some(item) => item,
+            None => return false,
+        };
+        self.equipped.insert(slot, item);
+        if let Some(previous) = previous {
+            self.add(previous, 1);
+        }
+        true
+    }
+
+    /*
+    Take off whatever is in slot, as long as there's room for it in the inventory
+     */
+    pub fn unequip(&mut self, slot: EquipSlot, items: &ItemDatabase) -> bool {
+        let item = match self.equipped.get(&slot).and_then(|id| items.get(id)) {
+            Some(item) => item,
+            None => return false,
+        };
+        if self.add(item, 1) > 0 {
+            return false;
+        }
+        self.equipped.remove(&slot);
+        true
+    }
+
+    // everything the equipped items add up to
+    pub fn modifiers(&self, items: &ItemDatabase) -> ItemModifiers {
+        let mut modifiers = ItemModifiers::default();
+        for item in self.equipped.values().filter_map(|id| items.get(id)) {
+            modifiers += item.modifiers;
+        }
+        modifiers
+    }
+}
+
+/*
+The item modifiers that have been applied to the player's Stats, Health and attack,
+kept so they can be taken off again when the equipment changes
+ */
+#[derive(Component, Default)]
+pub struct EquipmentBonus(pub ItemModifiers);
+
+/*
+Root node of the inventory panel, see draw_inventory_ui
+ */
+#[derive(Component)]
+pub struct InventoryPanel;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::items::Rarity;
+
+    fn item(id: &str, max_stack: u32, slot: Option<EquipSlot>) -> ItemDef {
+        ItemDef {
+            id: id.to_string(),
+            name: id.to_string(),
+            icon: 0,
+            rarity: Rarity::Common,
+            slot,
+            max_stack,
+            modifiers: ItemModifiers::default(),
+            on_use: None,
+        }
+    }
+
+    fn items() -> ItemDatabase {
+        ItemDatabase(vec![
+            item("potion", 5, None),
+            item("sword", 1, Some(EquipSlot::Weapon)),
+            item("axe", 1, Some(EquipSlot::Weapon)),
+            item("knife", 3, Some(EquipSlot::Weapon)),
+        ])
+    }
+
+    fn counts(inventory: &Inventory) -> Vec<(&str, u32)> {
+        inventory.stacks.iter().map(|stack| (stack.item.as_str(), stack.count)).collect()
+    }
+
+    #[test]
+    fn add_tops_up_stacks_first() {
+        let items = items();
+        let potion = items.get("potion").unwrap();
+        let mut inventory = Inventory::new(3);
+
+        assert_eq!(inventory.add(potion, 3), 0);
+        assert_eq!(inventory.add(potion, 4), 0);
+        assert_eq!(counts(&inventory), vec![("potion", 5), ("potion", 2)]);
+
+        inventory.add(items.get("sword").unwrap(), 1);
+        assert_eq!(inventory.add(potion, 2), 0);
+        assert_eq!(counts(&inventory), vec![("potion", 5), ("potion", 4), ("sword", 1)]);
+    }
+
+    #[test]
+    fn add_returns_what_didnt_fit() {
+        let items = items();
+        let mut inventory = Inventory::new(2);
+
+        assert_eq!(inventory.add(items.get("potion").unwrap(), 12), 2);
+        assert_eq!(counts(&inventory), vec![("potion", 5), ("potion", 5)]);
+        assert_eq!(inventory.add(items.get("sword").unwrap(), 1), 1);
+        assert_eq!(inventory.stacks.len(), 2);
+    }
+
+    #[test]
+    fn take_empties_stacks() {
+        let items = items();
+        let mut inventory = Inventory::new(3);
+        inventory.add(items.get("potion").unwrap(), 2);
+
+        assert_eq!(inventory.take(0).as_deref(), Some("potion"));
+        assert_eq!(counts(&inventory), vec![("potion", 1)]);
+        assert_eq!(inventory.take(0).as_deref(), Some("potion"));
+        assert!(inventory.stacks.is_empty());
+        assert_eq!(inventory.take(0), None);
+    }
+
+    #[test]
+    fn equip_swaps_with_what_was_worn() {
+        let items = items();
+        let mut inventory = Inventory::new(2);
+        inventory.add(items.get("sword").unwrap(), 1);
+        inventory.add(items.get("axe").unwrap(), 1);
+
+        assert!(inventory.equip(0, &items));
+        assert_eq!(inventory.equipped.get(&EquipSlot::Weapon).map(String::as_str), Some("sword"));
+        assert_eq!(counts(&inventory), vec![("axe", 1)]);
+
+        assert!(inventory.equip(0, &items));
+        assert_eq!(inventory.equipped.get(&EquipSlot::Weapon).map(String::as_str), Some("axe"));
+        assert_eq!(counts(&inventory), vec![("sword", 1)]);
+
+        // potions can't be worn
+        inventory.add(items.get("potion").unwrap(), 1);
+        assert!(!inventory.equip(1, &items));
+        assert!(!inventory.equip(5, &items));
+    }
+
+    #[test]
+    fn equip_with_a_full_inventory() {
+        let items = items();
+        let mut inventory = Inventory::new(2);
+        inventory.add(items.get("knife").unwrap(), 1);
+        inventory.equip(0, &items);
+        inventory.add(items.get("sword").unwrap(), 1);
+        inventory.add(items.get("potion").unwrap(), 1);
+
+        // the sword leaves a slot free for the knife
+        assert!(inventory.equip(0, &items));
+        assert_eq!(counts(&inventory), vec![("potion", 1), ("knife", 1)]);
+
+        // one knife out of a stack of two leaves no slot for the sword
+        inventory.add(items.get("knife").unwrap(), 1);
+        assert!(!inventory.equip(1, &items));
+        assert_eq!(inventory.equipped.get(&EquipSlot::Weapon).map(String::as_str), Some("sword"));
+        assert_eq!(counts(&inventory), vec![("potion", 1), ("knife", 2)]);
+    }
+
+    #[test]
+    fn equip_tops_up_the_stack_of_the_swapped_item() {
+        let items = items();
+        let mut inventory = Inventory::new(1);
+        inventory.add(items.get("knife").unwrap(), 1);
+        inventory.equip(0, &items);
+        inventory.add(items.get("knife").unwrap(), 2);
+
+        // full, but the knife taken off goes back on top of the stack
+        assert!(inventory.equip(0, &items));
+        assert_eq!(counts(&inventory), vec![("knife", 2)]);
+    }
+
+    #[test]
+    fn unequip_needs_room() {
+        let items = items();
+        let mut inventory = Inventory::new(1);
+        inventory.add(items.get("sword").unwrap(), 1);
+        inventory.equip(0, &items);
+        inventory.add(items.get("potion").unwrap(), 1);
+
+        assert!(!inventory.unequip(EquipSlot::Weapon, &items));
+        assert!(inventory.equipped.contains_key(&EquipSlot::Weapon));
+
+        inventory.take(0);
+        assert!(inventory.unequip(EquipSlot::Weapon, &items));
+        assert!(inventory.equipped.is_empty());
+        assert_eq!(counts(&inventory), vec![("sword", 1)]);
+        assert!(!inventory.unequip(EquipSlot::Weapon, &items));
+    }
+}
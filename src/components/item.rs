@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/*
+An item lying on the floor, the player picks it up by walking over it
+ */
+#[derive(Component)]
+pub struct ItemPickup {
+    // id in items.items.ron
+    pub item: String,
+}
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use crate::resources::main_menu::MenuAction;
+
+/*
+Root node of the main menu, see draw_main_menu
+ */
+#[derive(Component)]
+pub struct MainMenuRoot;
+
+/*
+A row of the main menu that can be clicked, index is its place in the screen's actions
+ */
+#[derive(Component)]
+pub struct MenuButton {
+    pub action: MenuAction,
+    pub index: usize,
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::{Inspectable};
 
+#[allow(dead_code)]
 #[derive(Component)]
 pub struct Person;
 
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
-use crate::components::Health;
+use crate::components::{Health, Stamina};
+use crate::components::stats::Stats;
 
 #[allow(dead_code)]
 pub enum PlayerAnimationStates {
@@ -15,13 +16,29 @@ pub enum PlayerAnimationStates {
 #[derive(Component)]
 pub struct PlayerAnimationState(pub PlayerAnimationStates);
 
+/*
+Player is mid dodge-roll, moving in a fixed direction until the timer runs out
+ */
+#[derive(Component)]
+pub struct DodgeRoll {
+    pub direction: Vec2,
+    pub timer: Timer,
+}
+
 #[derive(Inspectable, Component)]
 pub struct PlayerXp(pub u32);
 
+// goes up as PlayerXp passes the thresholds in levels.level.ron, starts at 1
+#[derive(Inspectable, Component)]
+pub struct PlayerLevel(pub u32);
+
 #[derive(Bundle)]
 pub struct PlayerBundle {
     pub health: Health,
+    pub stamina: Stamina,
     pub xp: PlayerXp,
+    pub level: PlayerLevel,
+    pub stats: Stats,
     pub name: crate::components::person::Name,
     pub animation: PlayerAnimationState,
     #[bundle]
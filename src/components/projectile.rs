@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+
+use crate::components::status::{EffectKind, StatusEffect};
+use crate::components::LayerMask;
+
+/*
+Blast that hurts everything in range of where a projectile went off
+ */
+#[derive(Inspectable, Clone, Copy, Debug)]
+pub struct Explosion {
+    pub radius: f32,
+    pub damage: f32,
+    pub knockback: f32,
+    // seconds the blast lingers before the bomb is gone
+    pub duration: f32,
+}
+
+#[derive(Inspectable, Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProjectileKind {
+    // Mushroom
+    Spore,
+    // Flying eye
+    EyeBolt,
+    // Goblin, lobbed at where the player stands and goes off after a short fuse
+    Bomb,
+}
+
+impl ProjectileKind {
+    // the group of clips in animations.anim.ron, each has a "fly" clip
+    pub fn animation_set(&self) -> &'static str {
+        match self {
+            ProjectileKind::Spore => "spore",
+            ProjectileKind::EyeBolt => "eye_bolt",
+            ProjectileKind::Bomb => "bomb",
+        }
+    }
+
+    // units per second
+    pub fn speed(&self) -> f32 {
+        match self {
+            ProjectileKind::Spore => 120.0,
+            ProjectileKind::EyeBolt => 180.0,
+            ProjectileKind::Bomb => 110.0,
+        }
+    }
+
+    // damage and knockback on a direct hit, bombs don't go off on contact
+    pub fn damage(&self) -> Option<(f32, f32)> {
+        match self {
+            ProjectileKind::Spore => Some((6.0, 80.0)),
+            ProjectileKind::EyeBolt => Some((5.0, 60.0)),
+            ProjectileKind::Bomb => None,
+        }
+    }
+
+    // put on whatever a direct hit lands on
+    pub fn effect(&self) -> Option<StatusEffect> {
+        match self {
+            ProjectileKind::Spore => Some(StatusEffect::new(EffectKind::Poison(2.0), 4.0, 1.0)),
+            _ => None,
+        }
+    }
+
+    pub fn explosion(&self) -> Option<Explosion> {
+        match self {
+            ProjectileKind::Bomb => Some(Explosion {
+                radius: 40.0,
+                damage: 14.0,
+                knockback: 220.0,
+                duration: 0.5,
+            }),
+            _ => None,
+        }
+    }
+
+    // seconds before it falls apart on its own
+    pub fn lifetime(&self) -> f32 {
+        match self {
+            ProjectileKind::Spore => 1.5,
+            ProjectileKind::EyeBolt => 1.5,
+            ProjectileKind::Bomb => 2.0,
+        }
+    }
+
+    /*
+    Seconds it lies where it stopped, the fuse of a bomb and the burst of the rest
+    These are about as long as the clips in animations.anim.ron, but don't wait on them
+     */
+    pub fn linger(&self) -> f32 {
+        match self {
+            ProjectileKind::Spore => 0.25,
+            ProjectileKind::EyeBolt => 0.3,
+            ProjectileKind::Bomb => 0.75,
+        }
+    }
+
+    // size of the hitbox in world units
+    pub fn size(&self) -> Vec2 {
+        match self {
+            ProjectileKind::Spore => Vec2::splat(8.0),
+            ProjectileKind::EyeBolt => Vec2::splat(10.0),
+            ProjectileKind::Bomb => Vec2::splat(8.0),
+        }
+    }
+}
+
+/*
+Something flying through the air, it moves in a straight line until it hits a target
+or a wall, or runs out of time
+ */
+#[derive(Component)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    pub owner: Entity,
+    pub velocity: Vec2,
+    // layers it can hurt
+    pub targets: LayerMask,
+    // the damage of the kind is multiplied by this, stronger enemies fire stronger projectiles
+    pub damage_multiplier: f32,
+    pub(crate) lifetime: Timer,
+}
+
+/*
+Projectile has hit something and stopped, it plays out its burst or fuse and goes away
+ */
+#[derive(Component)]
+pub struct Impacted;
+
+/*
+Bomb has gone off, only the blast is left to play out
+ */
+#[derive(Component)]
+pub struct Exploded;
+
+/*
+Lets an enemy shoot, see EnemyKind::ranged_attack
+ */
+#[derive(Component)]
+pub struct RangedAttack {
+    pub projectile: ProjectileKind,
+    // only shoots at targets closer than this
+    pub range: f32,
+    pub(crate) swing: Timer,
+    pub(crate) cooldown: Timer,
+}
+
+impl RangedAttack {
+    pub fn new(projectile: ProjectileKind, range: f32, swing_time: f32, cooldown: f32) -> Self {
+        let mut swing = Timer::from_seconds(swing_time, false);
+        swing.tick(swing.duration());
+
+        Self {
+            projectile,
+            range,
+            swing,
+            // not straight away, so a room full of enemies doesn't open fire all at once
+            cooldown: Timer::from_seconds(cooldown, false),
+        }
+    }
+
+    pub fn is_swinging(&self) -> bool {
+        !self.swing.finished()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+
+    pub fn start(&mut self) {
+        self.swing.reset();
+        self.cooldown.reset();
+    }
+}
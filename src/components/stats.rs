@@ -0,0 +1,58 @@
+use std::ops::{AddAssign, SubAssign};
+
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+use serde::Deserialize;
+
+// what one point of each stat is worth
+const HP_PER_VITALITY: f32 = 10.0;
+const ARMOR_PER_VITALITY: f32 = 0.25;
+const DAMAGE_PER_STRENGTH: f32 = 2.0;
+const SPEED_PER_DEXTERITY: f32 = 0.03;
+
+/*
+Points put into each stat, on top of the base numbers of whoever has them
+Strength hits harder, Dexterity moves faster and Vitality gives hp and armor
+ */
+#[derive(Inspectable, Component, Clone, Copy, Default, Debug, Deserialize)]
+pub struct Stats {
+    pub strength: u32,
+    pub dexterity: u32,
+    pub vitality: u32,
+}
+
+impl Stats {
+    pub fn max_hp_bonus(&self) -> f32 {
+        self.vitality as f32 * HP_PER_VITALITY
+    }
+
+    pub fn armor_bonus(&self) -> f32 {
+        self.vitality as f32 * ARMOR_PER_VITALITY
+    }
+
+    // added to the damage of every melee swing
+    pub fn damage_bonus(&self) -> f32 {
+        self.strength as f32 * DAMAGE_PER_STRENGTH
+    }
+
+    // walking, running, blocking and dodging speeds are all multiplied by this
+    pub fn speed_multiplier(&self) -> f32 {
+        1.0 + self.dexterity as f32 * SPEED_PER_DEXTERITY
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, other: Self) {
+        self.strength += other.strength;
+        self.dexterity += other.dexterity;
+        self.vitality += other.vitality;
+    }
+}
+
+impl SubAssign for Stats {
+    fn sub_assign(&mut self, other: Self) {
+        self.strength = self.strength.saturating_sub(other.strength);
+        self.dexterity = self.dexterity.saturating_sub(other.dexterity);
+        self.vitality = self.vitality.saturating_sub(other.vitality);
+    }
+}
@@ -0,0 +1,101 @@
+use std::mem::discriminant;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+pub enum EffectKind {
+    // hp given back every tick
+    Regeneration(f32),
+    // hp lost every tick, armor doesn't help against it
+    Poison(f32),
+    // points added to the stat while it lasts
+    Strength(u32),
+    Dexterity(u32),
+    Armor(f32),
+    // the whole floor is shown on the map overlay
+    RevealMap,
+}
+
+impl EffectKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectKind::Regeneration(_) => "Regeneration",
+            EffectKind::Poison(_) => "Poison",
+            EffectKind::Strength(_) => "Strength",
+            EffectKind::Dexterity(_) => "Dexterity",
+            EffectKind::Armor(_) => "Ironskin",
+            EffectKind::RevealMap => "Clairvoyance",
+        }
+    }
+
+    // harmful effects can't be put on entities that are invulnerable
+    pub fn is_harmful(&self) -> bool {
+        matches!(self, EffectKind::Poison(_))
+    }
+
+    // regardless of how strong they are, an entity only has one effect of each kind at a time
+    pub fn same_kind(&self, other: &EffectKind) -> bool {
+        discriminant(self) == discriminant(other)
+    }
+}
+
+/*
+An effect and how long it lasts, as it is written in the data
+Effects that do something over time do it every tick seconds
+ */
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    pub duration: f32,
+    #[serde(default = "default_tick")]
+    pub tick: f32,
+}
+
+fn default_tick() -> f32 {
+    1.0
+}
+
+impl StatusEffect {
+    pub fn new(kind: EffectKind, duration: f32, tick: f32) -> Self {
+        Self { kind, duration, tick }
+    }
+}
+
+/*
+An effect that is on an entity, source is whoever put it there
+ */
+pub struct ActiveEffect {
+    pub effect: StatusEffect,
+    pub source: Entity,
+    pub(crate) remaining: Timer,
+    pub(crate) tick: Timer,
+}
+
+impl ActiveEffect {
+    pub fn new(effect: StatusEffect, source: Entity) -> Self {
+        Self {
+            effect,
+            source,
+            remaining: Timer::from_seconds(effect.duration, false),
+            tick: Timer::from_seconds(effect.tick.max(0.05), true),
+        }
+    }
+
+    pub fn seconds_left(&self) -> f32 {
+        (self.remaining.duration() - self.remaining.elapsed()).as_secs_f32()
+    }
+}
+
+/*
+Everything an entity is under, anything that can be poisoned, buffed and so on needs one
+Effects are put on with a StatusEffectEvent, see apply_status_effects
+ */
+#[derive(Component, Default)]
+pub struct StatusEffects(pub Vec<ActiveEffect>);
+
+impl StatusEffects {
+    pub fn has(&self, kind: &EffectKind) -> bool {
+        self.0.iter().any(|active| active.effect.kind.same_kind(kind))
+    }
+}
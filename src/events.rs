@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::components::status::StatusEffect;
+
+/*
+Collision events, sent by detect_collisions when the set of entities touching a collider changes
+They are sent from the point of view of every entity that tracks its collisions with a
+Collision component, so two tracked entities touching each other produce one event each
+ */
+
+// entity started touching a solid collider
+pub struct CollisionStarted {
+    pub entity: Entity,
+    pub other: Entity,
+}
+
+// entity stopped touching a solid collider, other may already have been despawned
+pub struct CollisionEnded {
+    pub entity: Entity,
+    pub other: Entity,
+}
+
+// other started overlapping a collider with ColliderType::Trigger
+pub struct TriggerEntered {
+    pub trigger: Entity,
+    pub other: Entity,
+}
+
+// other stopped overlapping the trigger, either of them may already have been despawned
+pub struct TriggerExited {
+    pub trigger: Entity,
+    pub other: Entity,
+}
+
+// a one-shot clip reached its last frame
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub clip: String,
+}
+
+/*
+Combat events
+Anything that wants to hurt an entity sends a HitEvent, apply_hits works out how much of it
+actually gets through and sends a DamageEvent for what was dealt
+ */
+
+// target was hit for damage before armor, knockback is the push velocity
+pub struct HitEvent {
+    pub target: Entity,
+    pub source: Entity,
+    pub damage: f32,
+    pub knockback: Vec2,
+}
+
+// target lost amount hp to source, blocked hits may have been stopped entirely (amount 0)
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Entity,
+    pub amount: f32,
+    pub blocked: bool,
+}
+
+// entity's hp reached zero, killer dealt the last hit
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub killer: Entity,
+}
+
+// effect was put on target by source, see apply_status_effects
+pub struct StatusEffectEvent {
+    pub target: Entity,
+    pub source: Entity,
+    pub effect: StatusEffect,
+}
+
+// entity picked up an item lying on the floor, item is its id
+pub struct ItemPickedUp {
+    pub entity: Entity,
+    pub item: String,
+}
+
+// entity went up to level, sent once for every level when several are gained at once
+pub struct LevelUpEvent {
+    pub entity: Entity,
+    pub level: u32,
+}
+
+// entity used up one of item, taken out of its inventory already
+pub struct ItemUsed {
+    pub entity: Entity,
+    pub item: String,
+}
+
+// a new game was started from the main menu, everything left of the old one goes
+pub struct NewGameStarted;
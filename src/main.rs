@@ -6,17 +6,28 @@ use bevy_asset_loader::prelude::*;
 use bevy_ecs_tilemap::TilemapPlugin;
 use bevy_inspector_egui::{WorldInspectorPlugin};
 
+use plugins::animation::AnimationPlugin;
+use plugins::combat::CombatPlugin;
 use plugins::dungeon_gen::DungeonGenPlugin;
+use plugins::enemy::EnemyPlugin;
 use plugins::game_state_plugin::GameStatePlugin;
+use plugins::hud::HudPlugin;
+use plugins::input::ActionInputPlugin;
+use plugins::inventory::InventoryPlugin;
+use plugins::leveling::LevelingPlugin;
+use plugins::loot::LootPlugin;
 use plugins::main_menu::MainMenuPlugin;
+use plugins::projectile::ProjectilePlugin;
+use plugins::status::StatusPlugin;
 use plugins::tilemap_debug::TilemapDebugPlugin;
 use resources::MyStates;
 
-use crate::components::{BoxCollider, Collision, LoadingText, Unknown};
+use crate::components::{BoxCollider, Collision, CollisionLayer, FloorEntity, LayerMask, LoadingText, Unknown};
 use crate::plugins::inspections::InspectionPlugin;
 use crate::resources::assets::MyAssets;
 
 mod components;
+mod events;
 mod systems;
 mod plugins;
 mod resources;
@@ -28,7 +39,8 @@ fn spawn_unknown(mut commands: Commands, assets: Res<MyAssets>) {
         collider: BoxCollider {
             width: 32.0,
             height: 32.0,
-            layer: 0,
+            membership: LayerMask::from_layers(&[CollisionLayer::Wall]),
+            collision_mask: LayerMask::ALL,
             offset: Vec2::new(0.0, 0.0),
             scale: Vec2::new(1.0, 1.0),
             collider_type: crate::components::ColliderType::Solid,
@@ -44,7 +56,8 @@ fn spawn_unknown(mut commands: Commands, assets: Res<MyAssets>) {
             visibility: Default::default(),
             computed_visibility: Default::default(),
         },
-    });
+    })
+    .insert(FloorEntity);
 
     info!("Test object generated")
 }
@@ -60,6 +73,8 @@ fn main() {
         
         .add_plugins(DefaultPlugins)
         .add_plugin(TilemapPlugin)
+        .add_plugin(ActionInputPlugin)
+        .add_plugin(AnimationPlugin)
         .add_loading_state(
             LoadingState::new(MyStates::AssetLoading)
                 .continue_to_state(MyStates::MainMenu)
@@ -69,6 +84,14 @@ fn main() {
         .add_plugin(WorldInspectorPlugin::new())
         .add_plugin(InspectionPlugin)
         .add_plugin(GameStatePlugin)
+        .add_plugin(CombatPlugin)
+        .add_plugin(EnemyPlugin)
+        .add_plugin(ProjectilePlugin)
+        .add_plugin(LevelingPlugin)
+        .add_plugin(LootPlugin)
+        .add_plugin(InventoryPlugin)
+        .add_plugin(StatusPlugin)
+        .add_plugin(HudPlugin)
         .add_plugin(LogDiagnosticsPlugin::default())
         .add_plugin(DungeonGenPlugin)
         .add_plugin(TilemapDebugPlugin)
@@ -4,4 +4,14 @@ pub mod game_state_plugin;
 pub mod world_generation;
 pub mod main_menu;
 pub mod dungeon_gen;
-pub mod tilemap_debug;
\ No newline at end of file
+pub mod tilemap_debug;
+pub mod input;
+pub mod animation;
+pub mod combat;
+pub mod enemy;
+pub mod projectile;
+pub mod leveling;
+pub mod loot;
+pub mod inventory;
+pub mod status;
+pub mod hud;
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::events::AnimationFinished;
+use crate::resources::animation::AnimationLibrary;
+use crate::resources::MyStates;
+use crate::systems::animation::animate_sprites;
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<AnimationLibrary>::new(&["anim.ron"]))
+            .add_event::<AnimationFinished>()
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(animate_sprites),
+            );
+    }
+}
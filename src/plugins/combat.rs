@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use crate::events::{DamageEvent, DeathEvent, HitEvent};
+use crate::resources::MyStates;
+use crate::systems::collision::update_solid_grid;
+use crate::systems::combat::{apply_hits, apply_knockback, expire_hitboxes, hitbox_hits, log_damage, player_attack, player_block, tick_attacks};
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HitEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(tick_attacks)
+                    .with_system(player_attack.after(tick_attacks))
+                    .with_system(player_block)
+                    .with_system(hitbox_hits)
+                    .with_system(apply_hits.after(hitbox_hits))
+                    .with_system(log_damage.after(apply_hits))
+                    .with_system(apply_knockback.after(update_solid_grid))
+                    .with_system(expire_hitboxes),
+            );
+    }
+}
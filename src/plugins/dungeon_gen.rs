@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 use crate::resources::MyStates;
-use crate::systems::dungeon_gen::{Dungeon, gen_dungeon_system, gen_dungeon_stress_test};
+use crate::systems::dungeon_gen::{Dungeon, gen_dungeon_system};
 
 pub struct DungeonGenPlugin;
 
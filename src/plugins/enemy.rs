@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::resources::enemy_stats::EnemyStatsTable;
+use crate::resources::MyStates;
+use crate::systems::ai::{animate_enemies, despawn_dead_enemies, enemy_attack, enemy_block, enemy_shoot, kill_enemies, move_enemies, update_ai};
+use crate::systems::collision::update_solid_grid;
+use crate::systems::enemy::spawn_enemies;
+use crate::systems::pathfinding::follow_paths;
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<EnemyStatsTable>::new(&["stats.ron"]))
+            .add_system_set(
+                SystemSet::on_enter(MyStates::Game)
+                    .with_system(spawn_enemies),
+            )
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(kill_enemies)
+                    .with_system(update_ai.after(kill_enemies))
+                    .with_system(enemy_attack.after(update_ai))
+                    .with_system(enemy_shoot.after(update_ai))
+                    .with_system(enemy_block.after(update_ai))
+                    .with_system(follow_paths.after(update_ai))
+                    .with_system(move_enemies.after(follow_paths).after(update_solid_grid))
+                    .with_system(animate_enemies.after(enemy_attack))
+                    .with_system(despawn_dead_enemies),
+            );
+    }
+}
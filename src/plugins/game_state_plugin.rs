@@ -1,30 +1,45 @@
 use bevy::prelude::*;
 
-use crate::{systems::{player::{create_player, move_player, animate_player}}, spawn_unknown, resources::MyStates, components::camera::follow_player};
+use crate::{systems::{player::{create_player, move_player, animate_player, regenerate_stamina}, tick_invulnerable}, spawn_unknown, resources::MyStates, components::camera::follow_player};
 use crate::components::camera::CameraTimer;
+use crate::events::{CollisionEnded, CollisionStarted, TriggerEntered, TriggerExited};
 use crate::resources::RenderTimer;
-use crate::systems::world_gen::{despawn_outofrange_chunks, spawn_chunks_around_camera};
+use crate::systems::collision::{detect_collisions, log_collisions, update_solid_grid, SolidGrid};
+use crate::systems::world_gen::{ChunkManager, despawn_outofrange_chunks, descend_stairs, spawn_chunks_around_camera, spawn_stairs, spawn_wall_colliders};
 
 pub struct GameStatePlugin;
 
 impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CameraTimer(Timer::from_seconds(0.01, true)));
+        app.add_event::<CollisionStarted>()
+            .add_event::<CollisionEnded>()
+            .add_event::<TriggerEntered>()
+            .add_event::<TriggerExited>();
         app.insert_resource(RenderTimer(Timer::from_seconds(0.2, true)));
+        app.init_resource::<ChunkManager>();
+        app.init_resource::<SolidGrid>();
         app.add_system_set(
             SystemSet::on_enter(MyStates::Game)
                 //.with_system(draw_begining)
                 .with_system(create_player)
+                .with_system(spawn_wall_colliders)
+                .with_system(spawn_stairs)
                 .with_system(spawn_unknown),
         )
         .add_system_set(
             SystemSet::on_update(MyStates::Game)
-                .with_system(move_player)
+                .with_system(update_solid_grid)
+                .with_system(move_player.after(update_solid_grid))
                 .with_system(animate_player)
+                .with_system(regenerate_stamina)
+                .with_system(tick_invulnerable)
                 .with_system(follow_player)
-                .with_system(crate::systems::box_colliders)
+                .with_system(detect_collisions)
+                .with_system(log_collisions.after(detect_collisions))
                 .with_system(spawn_chunks_around_camera)
                 .with_system(despawn_outofrange_chunks)
+                .with_system(descend_stairs)
         );
     }
 }
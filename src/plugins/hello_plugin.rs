@@ -2,6 +2,7 @@ use bevy::prelude::{App, Plugin, Timer};
 use crate::resources::GreetTimer;
 use crate::systems::people::{add_people, greet_people};
 
+#[allow(dead_code)]
 pub struct HelloPlugin;
 
 impl Plugin for HelloPlugin {
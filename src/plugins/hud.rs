@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+use crate::resources::MyStates;
+use crate::systems::hud::{spawn_hud, update_floor_hud, update_health_hud, update_status_hud, update_xp_hud};
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(MyStates::Game).with_system(spawn_hud))
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(update_health_hud)
+                    .with_system(update_xp_hud)
+                    .with_system(update_floor_hud)
+                    .with_system(update_status_hud),
+            );
+    }
+}
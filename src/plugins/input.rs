@@ -0,0 +1,17 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::resources::input::{ActionInput, InputBindings, INPUT_CONFIG_PATH};
+use crate::systems::input::{save_input_bindings, update_action_input};
+
+pub struct ActionInputPlugin;
+
+impl Plugin for ActionInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load_or_default(INPUT_CONFIG_PATH))
+            .init_resource::<ActionInput>()
+            // actions are read during Update, so they have to be ready before it
+            .add_system_to_stage(CoreStage::PreUpdate, update_action_input.after(InputSystem))
+            .add_system(save_input_bindings);
+    }
+}
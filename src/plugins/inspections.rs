@@ -1,11 +1,29 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::{RegisterInspectable};
-use crate::Collision;
+use crate::{BoxCollider, Collision};
+use crate::components::{CircleCollider, Health, Stamina};
+use crate::components::combat::{Knockback, Shield};
+use crate::components::ai::Ai;
+use crate::components::enemy::{EnemyKind, EnemyLevel};
+use crate::components::player::{PlayerLevel, PlayerXp};
+use crate::components::stats::Stats;
 
 pub struct InspectionPlugin;
 
 impl Plugin for InspectionPlugin {
     fn build(&self, app: &mut App) {
-        app.register_inspectable::<Collision>();
+        app.register_inspectable::<Collision>()
+            .register_inspectable::<BoxCollider>()
+            .register_inspectable::<CircleCollider>()
+            .register_inspectable::<Stamina>()
+            .register_inspectable::<Health>()
+            .register_inspectable::<Knockback>()
+            .register_inspectable::<EnemyKind>()
+            .register_inspectable::<EnemyLevel>()
+            .register_inspectable::<Ai>()
+            .register_inspectable::<Shield>()
+            .register_inspectable::<PlayerXp>()
+            .register_inspectable::<PlayerLevel>()
+            .register_inspectable::<Stats>();
     }
 }
\ No newline at end of file
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::events::ItemUsed;
+use crate::resources::inventory::InventoryUi;
+use crate::resources::MyStates;
+use crate::systems::inventory::{apply_equipment, draw_inventory_ui, navigate_inventory, toggle_inventory, use_items};
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InventoryUi>()
+            .add_event::<ItemUsed>()
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(toggle_inventory)
+                    .with_system(navigate_inventory.after(toggle_inventory))
+                    .with_system(apply_equipment.after(navigate_inventory))
+                    .with_system(use_items.after(navigate_inventory))
+                    .with_system(draw_inventory_ui.after(navigate_inventory)),
+            );
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::events::LevelUpEvent;
+use crate::resources::leveling::LevelCurve;
+use crate::resources::MyStates;
+use crate::systems::leveling::{announce_level_ups, award_xp, level_up};
+
+pub struct LevelingPlugin;
+
+impl Plugin for LevelingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<LevelCurve>::new(&["level.ron"]))
+            .add_event::<LevelUpEvent>()
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(award_xp)
+                    .with_system(level_up.after(award_xp))
+                    .with_system(announce_level_ups.after(level_up)),
+            );
+    }
+}
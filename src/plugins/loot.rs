@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::events::ItemPickedUp;
+use crate::resources::items::ItemDatabase;
+use crate::resources::loot::LootTables;
+use crate::resources::MyStates;
+use crate::systems::loot::{announce_pickups, collect_pickups, drop_enemy_loot, spawn_room_loot};
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<ItemDatabase>::new(&["items.ron"]))
+            .add_plugin(RonAssetPlugin::<LootTables>::new(&["drops.ron"]))
+            .add_event::<ItemPickedUp>()
+            .add_system_set(
+                SystemSet::on_enter(MyStates::Game)
+                    .with_system(spawn_room_loot),
+            )
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(drop_enemy_loot)
+                    .with_system(collect_pickups)
+                    .with_system(announce_pickups.after(collect_pickups)),
+            );
+    }
+}
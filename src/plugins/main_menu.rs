@@ -1,20 +1,44 @@
 use bevy::prelude::*;
 
 use crate::{systems::draw_begining, resources::MyStates};
+use crate::events::NewGameStarted;
+use crate::resources::main_menu::MainMenuUi;
+use crate::systems::main_menu::{clear_game, close_main_menu, draw_main_menu, navigate_main_menu, open_main_menu, pause_game, type_seed};
 
 
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<MainMenuUi>()
+            .add_event::<NewGameStarted>();
         app.add_system_set(
           SystemSet::on_enter(MyStates::MainMenu)
 
               .with_system(draw_begining)
+              .with_system(open_main_menu)
+        );
+        app.add_system_set(
+          SystemSet::on_update(MyStates::MainMenu)
+              .with_system(type_seed)
+              .with_system(navigate_main_menu.after(type_seed))
+              .with_system(draw_main_menu.after(navigate_main_menu))
+        );
+        app.add_system_set(
+          SystemSet::on_exit(MyStates::MainMenu)
+              .with_system(close_main_menu)
+        );
+        app.add_system_set(
+          SystemSet::on_update(MyStates::Game)
+              .with_system(pause_game)
+        );
+        app.add_system_set(
+          SystemSet::on_exit(MyStates::Game)
+              .with_system(clear_game)
         );
         app.add_system_set(
           SystemSet::on_exit(MyStates::DungeonGeneration)
               .with_system(crate::systems::remove_loading_text)
         );
     }
-}
\ No newline at end of file
+}
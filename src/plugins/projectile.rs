@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use crate::resources::MyStates;
+use crate::systems::projectile::{finish_projectiles, impact_projectiles, move_projectiles, projectile_hits};
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(MyStates::Game)
+                .with_system(move_projectiles)
+                .with_system(projectile_hits)
+                .with_system(impact_projectiles)
+                .with_system(finish_projectiles.after(impact_projectiles)),
+        );
+    }
+}
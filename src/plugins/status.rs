@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::events::StatusEffectEvent;
+use crate::resources::MyStates;
+use crate::systems::map::show_map_overlay;
+use crate::systems::status::{apply_status_effects, tick_status_effects};
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StatusEffectEvent>()
+            .add_system_set(
+                SystemSet::on_update(MyStates::Game)
+                    .with_system(apply_status_effects)
+                    .with_system(tick_status_effects.after(apply_status_effects))
+                    .with_system(show_map_overlay.after(tick_status_effects)),
+            );
+    }
+}
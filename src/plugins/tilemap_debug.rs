@@ -1,11 +1,13 @@
 use bevy::prelude::Plugin;
 
 use bevy::prelude::*;
+#[allow(unused_imports)]
 use bevy_ecs_tilemap::prelude::*;
 
 pub struct TilemapDebugPlugin;
 
 impl Plugin for TilemapDebugPlugin {
+    #[allow(unused_variables)]
     fn build(&self, app: &mut App) {
       //app.add_system(tilemap_debug_system);
     }
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use crate::resources::MyStates;
 
 
+#[allow(dead_code)]
 pub struct WorldGenerationPlugin;
 
 impl Plugin for WorldGenerationPlugin {
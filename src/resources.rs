@@ -1,10 +1,19 @@
 use bevy_ecs_tilemap::prelude::*;
 use bevy::prelude::*;
 
+pub mod animation;
 pub mod assets;
+pub mod enemy_stats;
+pub mod input;
+pub mod inventory;
+pub mod items;
+pub mod leveling;
+pub mod loot;
+pub mod main_menu;
 
 use bevy::prelude::Timer;
 
+#[allow(dead_code)]
 pub struct GreetTimer(pub Timer);
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
@@ -41,11 +50,13 @@ pub const WORLD_SIZE: UVec2 = UVec2 {
     y: 128,
 };
 
+#[allow(dead_code)]
 pub struct WorldMap {
     pub map: Vec<Vec<u32>>,
 }
 
 impl Default for WorldMap {
+    #[allow(clippy::same_item_push)]
     fn default() -> Self {
         let mut map = Vec::new();
         for _ in 0..WORLD_SIZE.x {
@@ -0,0 +1,43 @@
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+/*
+One animation, a run of frames in a texture atlas played at a fixed rate
+ */
+#[derive(Clone, Debug, Deserialize)]
+pub struct AnimationClip {
+    // name of the atlas in MyAssets, see MyAssets::atlas
+    pub atlas: String,
+    pub first: usize,
+    pub last: usize,
+    pub fps: f32,
+    // one-shot clips stop on their last frame and send AnimationFinished
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+impl AnimationClip {
+    pub fn frame_count(&self) -> usize {
+        self.last - self.first + 1
+    }
+}
+
+/*
+Every animation in the game, loaded from assets/animations.anim.ron
+Clips are grouped by what they animate ("player", "goblin", ...) and then by name ("idle", "run", ...)
+ */
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "8d2f5a37-0c4e-4b1a-9f3e-6a7b21c4d9e0"]
+pub struct AnimationLibrary(pub HashMap<String, HashMap<String, AnimationClip>>);
+
+impl AnimationLibrary {
+    pub fn clip(&self, set: &str, name: &str) -> Option<&AnimationClip> {
+        self.0.get(set)?.get(name)
+    }
+}
@@ -1,22 +1,196 @@
 use bevy_asset_loader::prelude::*;
 use bevy::prelude::*;
 
+use crate::resources::animation::AnimationLibrary;
+use crate::resources::enemy_stats::EnemyStatsTable;
+use crate::resources::items::ItemDatabase;
+use crate::resources::leveling::LevelCurve;
+use crate::resources::loot::LootTables;
+
 #[derive(AssetCollection)]
 pub struct MyAssets {
-    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 9, rows = 8))]
+    // 8 columns x 9 rows, one animation per row
+    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 8, rows = 9))]
     #[asset(path = "AnimationSheet_Character.png")]
     pub player: Handle<TextureAtlas>,
 
     #[asset(path = "unknown.png")]
     pub unknown: Handle<Image>,
 
-    #[asset(path = "TX Tileset Grass.png")]
-    pub grasses_raw: Handle<Image>,
-
-    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 23, rows = 40))]
+    // used as a single texture by the chunk tilemaps, 40 columns x 22 rows of 32x32 tiles
     #[asset(path = "TXTilemap.png")]
-    pub base_tilemap: Handle<TextureAtlas>,
+    pub base_tilemap: Handle<Image>,
+    // the same sheet cut into tiles, for the bits drawn as sprites on top of the tilemap
+    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 40, rows = 22))]
+    #[asset(path = "TXTilemap.png")]
+    pub dungeon_tiles: Handle<TextureAtlas>,
 
     #[asset(path = "font.ttf")]
     pub font: Handle<Font>,
-}
\ No newline at end of file
+
+    // the HUD, numbers and labels in VT323, headings in Modeseven
+    #[asset(path = "VT323-Regular.ttf")]
+    pub hud_font: Handle<Font>,
+    #[asset(path = "Modeseven-L3n5.ttf")]
+    pub hud_title_font: Handle<Font>,
+
+    #[asset(path = "animations.anim.ron")]
+    pub animations: Handle<AnimationLibrary>,
+
+    #[asset(path = "levels.level.ron")]
+    pub levels: Handle<LevelCurve>,
+
+    #[asset(path = "enemies.stats.ron")]
+    pub enemy_stats: Handle<EnemyStatsTable>,
+
+    #[asset(path = "items.items.ron")]
+    pub item_database: Handle<ItemDatabase>,
+
+    #[asset(path = "loot.drops.ron")]
+    pub loot_tables: Handle<LootTables>,
+
+    // item icons, 16 columns x 16 rows of 32x32 cells
+    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 16, rows = 16))]
+    #[asset(path = "TX Props.png")]
+    pub items: Handle<TextureAtlas>,
+
+    // monster sheets, every animation is its own strip of 150x150 frames
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Goblin/Idle.png")]
+    pub goblin_idle: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Goblin/Run.png")]
+    pub goblin_run: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Goblin/Attack.png")]
+    pub goblin_attack: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Goblin/Attack2.png")]
+    pub goblin_attack2: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 12, rows = 1))]
+    #[asset(path = "Goblin/Attack3.png")]
+    pub goblin_attack3: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Goblin/Take Hit.png")]
+    pub goblin_take_hit: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Goblin/Death.png")]
+    pub goblin_death: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Skeleton/Idle.png")]
+    pub skeleton_idle: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Skeleton/Walk.png")]
+    pub skeleton_walk: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Skeleton/Attack.png")]
+    pub skeleton_attack: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Skeleton/Attack2.png")]
+    pub skeleton_attack2: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 6, rows = 1))]
+    #[asset(path = "Skeleton/Attack3.png")]
+    pub skeleton_attack3: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Skeleton/Shield.png")]
+    pub skeleton_shield: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Skeleton/Take Hit.png")]
+    pub skeleton_take_hit: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Skeleton/Death.png")]
+    pub skeleton_death: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Mushroom/Idle.png")]
+    pub mushroom_idle: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Mushroom/Run.png")]
+    pub mushroom_run: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Mushroom/Attack.png")]
+    pub mushroom_attack: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Mushroom/Attack2.png")]
+    pub mushroom_attack2: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 11, rows = 1))]
+    #[asset(path = "Mushroom/Attack3.png")]
+    pub mushroom_attack3: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Mushroom/Take Hit.png")]
+    pub mushroom_take_hit: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Mushroom/Death.png")]
+    pub mushroom_death: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Flying eye/Flight.png")]
+    pub flying_eye_flight: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Flying eye/Attack.png")]
+    pub flying_eye_attack: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 8, rows = 1))]
+    #[asset(path = "Flying eye/Attack2.png")]
+    pub flying_eye_attack2: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 6, rows = 1))]
+    #[asset(path = "Flying eye/Attack3.png")]
+    pub flying_eye_attack3: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Flying eye/Take Hit.png")]
+    pub flying_eye_take_hit: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 150., tile_size_y = 150., columns = 4, rows = 1))]
+    #[asset(path = "Flying eye/Death.png")]
+    pub flying_eye_death: Handle<TextureAtlas>,
+
+    // projectiles, strips of square frames
+    #[asset(texture_atlas(tile_size_x = 50., tile_size_y = 50., columns = 8, rows = 1))]
+    #[asset(path = "Mushroom/Projectile_sprite.png")]
+    pub spore: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 48., tile_size_y = 48., columns = 8, rows = 1))]
+    #[asset(path = "Flying eye/projectile_sprite.png")]
+    pub eye_bolt: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 100., tile_size_y = 100., columns = 19, rows = 1))]
+    #[asset(path = "Goblin/Bomb_sprite.png")]
+    pub bomb: Handle<TextureAtlas>,
+}
+
+impl MyAssets {
+    /*
+    Look up an atlas by the name the animation data uses for it
+     */
+    pub fn atlas(&self, name: &str) -> Option<&Handle<TextureAtlas>> {
+        match name {
+            "player" => Some(&self.player),
+            "goblin/idle" => Some(&self.goblin_idle),
+            "goblin/run" => Some(&self.goblin_run),
+            "goblin/attack" => Some(&self.goblin_attack),
+            "goblin/attack2" => Some(&self.goblin_attack2),
+            "goblin/attack3" => Some(&self.goblin_attack3),
+            "goblin/take_hit" => Some(&self.goblin_take_hit),
+            "goblin/death" => Some(&self.goblin_death),
+            "skeleton/idle" => Some(&self.skeleton_idle),
+            "skeleton/walk" => Some(&self.skeleton_walk),
+            "skeleton/attack" => Some(&self.skeleton_attack),
+            "skeleton/attack2" => Some(&self.skeleton_attack2),
+            "skeleton/attack3" => Some(&self.skeleton_attack3),
+            "skeleton/shield" => Some(&self.skeleton_shield),
+            "skeleton/take_hit" => Some(&self.skeleton_take_hit),
+            "skeleton/death" => Some(&self.skeleton_death),
+            "mushroom/idle" => Some(&self.mushroom_idle),
+            "mushroom/run" => Some(&self.mushroom_run),
+            "mushroom/attack" => Some(&self.mushroom_attack),
+            "mushroom/attack2" => Some(&self.mushroom_attack2),
+            "mushroom/attack3" => Some(&self.mushroom_attack3),
+            "mushroom/take_hit" => Some(&self.mushroom_take_hit),
+            "mushroom/death" => Some(&self.mushroom_death),
+            "flying_eye/flight" => Some(&self.flying_eye_flight),
+            "flying_eye/attack" => Some(&self.flying_eye_attack),
+            "flying_eye/attack2" => Some(&self.flying_eye_attack2),
+            "flying_eye/attack3" => Some(&self.flying_eye_attack3),
+            "flying_eye/take_hit" => Some(&self.flying_eye_take_hit),
+            "flying_eye/death" => Some(&self.flying_eye_death),
+            "spore" => Some(&self.spore),
+            "eye_bolt" => Some(&self.eye_bolt),
+            "bomb" => Some(&self.bomb),
+            _ => None,
+        }
+    }
+}
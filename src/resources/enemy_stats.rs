@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+use crate::components::enemy::{EnemyKind, EnemyRank};
+
+/*
+The numbers of one monster kind at level 1
+ */
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct EnemyTemplate {
+    pub health: f32,
+    pub armor: f32,
+    // of the melee swing, projectiles are scaled by the same amount the swing is
+    pub damage: f32,
+    pub xp: f32,
+}
+
+/*
+Fraction of the template added for every level, speed is a fraction of the kind's AiTuning speed
+ */
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct LevelGrowth {
+    pub health: f32,
+    pub armor: f32,
+    pub damage: f32,
+    pub speed: f32,
+    pub xp: f32,
+}
+
+/*
+How much a rank multiplies the scaled stats by and what it looks like
+ */
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct RankModifier {
+    // chance for any enemy to get this rank, the rarest ranks are rolled first
+    pub chance: f64,
+    pub health: f32,
+    pub armor: f32,
+    pub damage: f32,
+    pub speed: f32,
+    pub xp: f32,
+    // size of the sprite and body compared to a normal one
+    pub scale: f32,
+    // rgb multiplied with the sprite
+    pub tint: (f32, f32, f32),
+}
+
+/*
+Enemy stats, loaded from assets/enemies.stats.ron
+Every kind has a template, which grows by per_level (a fraction of the template) for every level
+an enemy has over 1 and is then multiplied by its rank
+An enemy's level comes from the floor of the dungeon and the level of the player
+ */
+#[derive(Debug, Deserialize, TypeUuid)]
+#[uuid = "c71a4e02-95d3-4f6b-8e2a-1d3b5f7c9e64"]
+pub struct EnemyStatsTable {
+    pub templates: HashMap<EnemyKind, EnemyTemplate>,
+    pub per_level: LevelGrowth,
+    // levels added for every floor below the first and every player level above the first
+    pub levels_per_floor: f32,
+    pub levels_per_player_level: f32,
+    pub ranks: HashMap<EnemyRank, RankModifier>,
+}
+
+/*
+Stats of one enemy, ready to go on its components
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct EnemyStats {
+    pub level: u32,
+    pub rank: EnemyRank,
+    pub health: f32,
+    pub armor: f32,
+    pub damage: f32,
+    // what the template damage got multiplied by
+    pub damage_multiplier: f32,
+    // AiTuning speed gets multiplied by this
+    pub speed_multiplier: f32,
+    pub xp: u32,
+    pub scale: f32,
+    pub tint: Color,
+}
+
+impl EnemyStatsTable {
+    pub fn level(&self, floor: u32, player_level: u32) -> u32 {
+        let levels = self.levels_per_floor * floor.saturating_sub(1) as f32
+            + self.levels_per_player_level * player_level.saturating_sub(1) as f32;
+        1 + levels as u32
+    }
+
+    /*
+    Pick a rank with the chances in the table, roll is from 0 to 1
+     */
+    pub fn rank(&self, roll: f64) -> EnemyRank {
+        let mut total = 0.0;
+        for rank in EnemyRank::RARE {
+            if let Some(modifier) = self.ranks.get(&rank) {
+                total += modifier.chance;
+                if roll < total {
+                    return rank;
+                }
+            }
+        }
+        EnemyRank::Normal
+    }
+
+    /*
+    The stats of kind at level and rank, None if the table has no template for kind
+     */
+    pub fn stats(&self, kind: EnemyKind, level: u32, rank: EnemyRank) -> Option<EnemyStats> {
+        let template = self.templates.get(&kind)?;
+        let levels = level.saturating_sub(1) as f32;
+        let grow = |base: f32, per_level: f32| base * (1.0 + per_level * levels);
+
+        let modifier = self.ranks.get(&rank).copied().unwrap_or(RankModifier {
+            chance: 0.0,
+            health: 1.0,
+            armor: 1.0,
+            damage: 1.0,
+            speed: 1.0,
+            xp: 1.0,
+            scale: 1.0,
+            tint: (1.0, 1.0, 1.0),
+        });
+        let damage = grow(template.damage, self.per_level.damage) * modifier.damage;
+
+        Some(EnemyStats {
+            level,
+            rank,
+            health: grow(template.health, self.per_level.health) * modifier.health,
+            armor: grow(template.armor, self.per_level.armor) * modifier.armor,
+            damage,
+            damage_multiplier: if template.damage > 0.0 { damage / template.damage } else { 1.0 },
+            speed_multiplier: grow(1.0, self.per_level.speed) * modifier.speed,
+            xp: (grow(template.xp, self.per_level.xp) * modifier.xp).round() as u32,
+            scale: modifier.scale,
+            tint: Color::rgb(modifier.tint.0, modifier.tint.1, modifier.tint.2),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> EnemyStatsTable {
+        ron::from_str(
+            r#"(
+                templates: {
+                    Goblin: (health: 30.0, armor: 2.0, damage: 6.0, xp: 10.0),
+                },
+                per_level: (health: 0.5, armor: 0.5, damage: 0.25, speed: 0.1, xp: 1.0),
+                levels_per_floor: 1.0,
+                levels_per_player_level: 0.5,
+                ranks: {
+                    Champion: (chance: 0.1, health: 3.0, armor: 2.0, damage: 2.0, speed: 1.5, xp: 5.0, scale: 1.5, tint: (1.0, 0.0, 0.0)),
+                    Elite: (chance: 0.2, health: 2.0, armor: 1.0, damage: 1.5, speed: 1.0, xp: 2.0, scale: 1.2, tint: (0.0, 1.0, 0.0)),
+                },
+            )"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn shipped_table_loads() {
+        let table: EnemyStatsTable = ron::from_str(include_str!("../../assets/enemies.stats.ron")).unwrap();
+        for kind in EnemyKind::ALL {
+            assert!(table.stats(kind, 1, EnemyRank::Normal).is_some(), "no stats for {:?}", kind);
+        }
+    }
+
+    #[test]
+    fn rank_picks_rarest_first() {
+        let table = table();
+
+        assert_eq!(table.rank(0.0), EnemyRank::Champion);
+        assert_eq!(table.rank(0.09), EnemyRank::Champion);
+        assert_eq!(table.rank(0.1), EnemyRank::Elite);
+        assert_eq!(table.rank(0.29), EnemyRank::Elite);
+        assert_eq!(table.rank(0.31), EnemyRank::Normal);
+        assert_eq!(table.rank(0.99), EnemyRank::Normal);
+    }
+
+    #[test]
+    fn level_grows_with_floor_and_player_level() {
+        let table = table();
+
+        assert_eq!(table.level(1, 1), 1);
+        assert_eq!(table.level(3, 1), 3);
+        // half a level for every player level, rounded down
+        assert_eq!(table.level(1, 2), 1);
+        assert_eq!(table.level(1, 3), 2);
+        assert_eq!(table.level(0, 0), 1);
+    }
+
+    #[test]
+    fn stats_scale_with_level_and_rank() {
+        let table = table();
+
+        let normal = table.stats(EnemyKind::Goblin, 1, EnemyRank::Normal).unwrap();
+        assert_eq!((normal.health, normal.armor, normal.damage, normal.xp), (30.0, 2.0, 6.0, 10));
+        assert_eq!((normal.damage_multiplier, normal.speed_multiplier, normal.scale), (1.0, 1.0, 1.0));
+
+        let level_3 = table.stats(EnemyKind::Goblin, 3, EnemyRank::Normal).unwrap();
+        assert_eq!((level_3.health, level_3.armor, level_3.damage, level_3.xp), (60.0, 4.0, 9.0, 30));
+        assert_eq!(level_3.damage_multiplier, 1.5);
+        assert!((level_3.speed_multiplier - 1.2).abs() < 1e-6);
+
+        let champion = table.stats(EnemyKind::Goblin, 3, EnemyRank::Champion).unwrap();
+        assert_eq!((champion.health, champion.armor, champion.damage, champion.xp), (180.0, 8.0, 18.0, 150));
+        assert_eq!((champion.damage_multiplier, champion.scale), (3.0, 1.5));
+        assert_eq!(champion.rank, EnemyRank::Champion);
+
+        assert!(table.stats(EnemyKind::Skeleton, 1, EnemyRank::Normal).is_none());
+    }
+}
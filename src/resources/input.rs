@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use serde::{Deserialize, Serialize};
+
+// where the bindings are read from on startup and written back to when they change
+pub const INPUT_CONFIG_PATH: &str = "config/input.ron";
+
+/*
+Everything the player can do, gameplay systems read these instead of specific keys
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Run,
+    Dodge,
+    Attack,
+    Block,
+    Interact,
+    Inventory,
+    Pause,
+}
+
+impl InputAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move up",
+            InputAction::MoveDown => "Move down",
+            InputAction::MoveLeft => "Move left",
+            InputAction::MoveRight => "Move right",
+            InputAction::Run => "Run",
+            InputAction::Dodge => "Dodge",
+            InputAction::Attack => "Attack",
+            InputAction::Block => "Block",
+            InputAction::Interact => "Interact",
+            InputAction::Inventory => "Inventory",
+            InputAction::Pause => "Pause",
+        }
+    }
+}
+
+/*
+Which keys and gamepad buttons trigger each action
+The left stick always moves the player, on top of whatever is bound to the Move actions
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputBindings {
+    pub keys: BTreeMap<InputAction, Vec<KeyCode>>,
+    pub gamepad_buttons: BTreeMap<InputAction, Vec<GamepadButtonType>>,
+    pub stick_dead_zone: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let keys = BTreeMap::from([
+            (InputAction::MoveUp, vec![KeyCode::W, KeyCode::Up]),
+            (InputAction::MoveDown, vec![KeyCode::S, KeyCode::Down]),
+            (InputAction::MoveLeft, vec![KeyCode::A, KeyCode::Left]),
+            (InputAction::MoveRight, vec![KeyCode::D, KeyCode::Right]),
+            (InputAction::Run, vec![KeyCode::LShift]),
+            (InputAction::Dodge, vec![KeyCode::LControl, KeyCode::K]),
+            (InputAction::Attack, vec![KeyCode::Space, KeyCode::J]),
+            (InputAction::Block, vec![KeyCode::L]),
+            (InputAction::Interact, vec![KeyCode::E]),
+            (InputAction::Inventory, vec![KeyCode::I, KeyCode::Tab]),
+            (InputAction::Pause, vec![KeyCode::Escape]),
+        ]);
+
+        let gamepad_buttons = BTreeMap::from([
+            (InputAction::MoveUp, vec![GamepadButtonType::DPadUp]),
+            (InputAction::MoveDown, vec![GamepadButtonType::DPadDown]),
+            (InputAction::MoveLeft, vec![GamepadButtonType::DPadLeft]),
+            (InputAction::MoveRight, vec![GamepadButtonType::DPadRight]),
+            (InputAction::Run, vec![GamepadButtonType::LeftTrigger2]),
+            (InputAction::Dodge, vec![GamepadButtonType::East]),
+            (InputAction::Attack, vec![GamepadButtonType::West]),
+            (InputAction::Block, vec![GamepadButtonType::LeftTrigger]),
+            (InputAction::Interact, vec![GamepadButtonType::South]),
+            (InputAction::Inventory, vec![GamepadButtonType::North]),
+            (InputAction::Pause, vec![GamepadButtonType::Start]),
+        ]);
+
+        Self {
+            keys,
+            gamepad_buttons,
+            stick_dead_zone: 0.2,
+        }
+    }
+}
+
+impl InputBindings {
+    /*
+    Read the bindings from the config file
+    If there is no file yet the defaults are written out, so there is something to edit
+    A file that can't be read or parsed is left alone and the defaults are used instead
+    Actions missing from the file (added since it was written) get their default bindings
+     */
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(contents) => match ron::from_str::<Self>(&contents) {
+                Ok(mut bindings) => {
+                    let defaults = Self::default();
+                    for (action, keys) in defaults.keys {
+                        bindings.keys.entry(action).or_insert(keys);
+                    }
+                    for (action, buttons) in defaults.gamepad_buttons {
+                        bindings.gamepad_buttons.entry(action).or_insert(buttons);
+                    }
+                    bindings
+                }
+                Err(e) => {
+                    warn!("Could not parse input bindings from {}: {}", path.display(), e);
+                    Self::default()
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let bindings = Self::default();
+                if let Err(e) = bindings.save(path) {
+                    warn!("Could not write default input bindings to {}: {}", path.display(), e);
+                }
+                bindings
+            }
+            Err(e) => {
+                warn!("Could not read input bindings from {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, contents)
+    }
+
+    /*
+    Bind key to action, replacing the keys the action had
+    A key only ever triggers one action, so it is taken off any other action first
+     */
+    pub fn rebind_key(&mut self, action: InputAction, key: KeyCode) {
+        for keys in self.keys.values_mut() {
+            keys.retain(|k| *k != key);
+        }
+        self.keys.insert(action, vec![key]);
+    }
+
+    // same as rebind_key, for gamepad buttons
+    pub fn rebind_button(&mut self, action: InputAction, button: GamepadButtonType) {
+        for buttons in self.gamepad_buttons.values_mut() {
+            buttons.retain(|b| *b != button);
+        }
+        self.gamepad_buttons.insert(action, vec![button]);
+    }
+}
+
+/*
+The actions being triggered this frame, rebuilt from the raw input by update_action_input
+ */
+#[derive(Default)]
+pub struct ActionInput {
+    pub(crate) pressed: HashSet<InputAction>,
+    pub(crate) just_pressed: HashSet<InputAction>,
+    pub(crate) movement: Vec2,
+}
+
+impl ActionInput {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /*
+    Whether action was just pressed, taking it so nothing that runs after this frame sees it
+    For actions that switch states, where the state switched to would otherwise see the same press
+     */
+    pub fn consume(&mut self, action: InputAction) -> bool {
+        self.just_pressed.remove(&action)
+    }
+
+    /*
+    Direction the player wants to move in, no longer than 1
+    Keys give full speed, the stick can be pushed part of the way
+     */
+    pub fn movement(&self) -> Vec2 {
+        self.movement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_a_key_takes_it_off_the_other_action() {
+        let mut bindings = InputBindings::default();
+
+        bindings.rebind_key(InputAction::Interact, KeyCode::Space);
+        assert_eq!(bindings.keys[&InputAction::Interact], vec![KeyCode::Space]);
+        assert_eq!(bindings.keys[&InputAction::Attack], vec![KeyCode::J]);
+
+        bindings.rebind_button(InputAction::Attack, GamepadButtonType::South);
+        assert_eq!(bindings.gamepad_buttons[&InputAction::Attack], vec![GamepadButtonType::South]);
+        assert!(bindings.gamepad_buttons[&InputAction::Interact].is_empty());
+    }
+
+    #[test]
+    fn every_action_has_default_bindings() {
+        let bindings = InputBindings::default();
+        assert_eq!(bindings.keys.len(), bindings.gamepad_buttons.len());
+        for (action, keys) in bindings.keys.iter() {
+            assert!(!keys.is_empty(), "{:?} has no keys", action);
+            assert!(!bindings.gamepad_buttons[action].is_empty(), "{:?} has no buttons", action);
+        }
+    }
+}
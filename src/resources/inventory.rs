@@ -0,0 +1,9 @@
+/*
+Whether the inventory panel is open and which of its rows is selected
+The rows are the inventory's stacks followed by the equipment slots
+ */
+#[derive(Default)]
+pub struct InventoryUi {
+    pub open: bool,
+    pub selected: usize,
+}
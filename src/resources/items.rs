@@ -0,0 +1,134 @@
+use std::ops::AddAssign;
+
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+use crate::components::stats::Stats;
+use crate::components::status::StatusEffect;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Deserialize)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    // what the item's name is shown in
+    pub fn color(&self) -> Color {
+        match self {
+            Rarity::Common => Color::rgb(0.85, 0.85, 0.85),
+            Rarity::Uncommon => Color::rgb(0.35, 0.85, 0.35),
+            Rarity::Rare => Color::rgb(0.35, 0.55, 1.0),
+            Rarity::Epic => Color::rgb(0.75, 0.4, 1.0),
+        }
+    }
+}
+
+/*
+Where an item is worn, every slot holds one item
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Accessory,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Weapon, EquipSlot::Armor, EquipSlot::Accessory];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "Weapon",
+            EquipSlot::Armor => "Armor",
+            EquipSlot::Accessory => "Accessory",
+        }
+    }
+}
+
+/*
+What wearing an item adds to whoever wears it, anything left out of the data is 0
+ */
+#[derive(Clone, Copy, Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct ItemModifiers {
+    pub strength: u32,
+    pub dexterity: u32,
+    pub vitality: u32,
+    pub armor: f32,
+    pub damage: f32,
+}
+
+impl AddAssign for ItemModifiers {
+    fn add_assign(&mut self, other: Self) {
+        self.strength += other.strength;
+        self.dexterity += other.dexterity;
+        self.vitality += other.vitality;
+        self.armor += other.armor;
+        self.damage += other.damage;
+    }
+}
+
+impl ItemModifiers {
+    pub fn stats(&self) -> Stats {
+        Stats {
+            strength: self.strength,
+            dexterity: self.dexterity,
+            vitality: self.vitality,
+        }
+    }
+}
+
+/*
+What happens when an item is used from the inventory, the item is used up doing it
+ */
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum ItemUse {
+    // hp given back at once
+    Heal(f32),
+    // put on whoever used it
+    Effect(StatusEffect),
+    TeleportToStairs,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ItemDef {
+    // what drop tables and inventories refer to the item by
+    pub id: String,
+    pub name: String,
+    // index into the items atlas, see MyAssets::items
+    pub icon: usize,
+    pub rarity: Rarity,
+    // items that can be worn, the rest stay in the inventory
+    #[serde(default)]
+    pub slot: Option<EquipSlot>,
+    // how many fit in one inventory slot
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
+    #[serde(default)]
+    pub modifiers: ItemModifiers,
+    // items that can be used up, worn items can't be
+    #[serde(default)]
+    pub on_use: Option<ItemUse>,
+}
+
+fn default_max_stack() -> u32 {
+    1
+}
+
+/*
+Every item in the game, loaded from assets/items.items.ron
+ */
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "5e0f3a9c-2b7d-4c61-8a4e-9d1c6b3f2a75"]
+pub struct ItemDatabase(pub Vec<ItemDef>);
+
+impl ItemDatabase {
+    pub fn get(&self, id: &str) -> Option<&ItemDef> {
+        self.0.iter().find(|item| item.id == id)
+    }
+}
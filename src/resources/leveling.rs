@@ -0,0 +1,75 @@
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+use crate::components::stats::Stats;
+
+/*
+How much xp every level takes and what it gives, loaded from assets/levels.level.ron
+ */
+#[derive(Debug, Deserialize, TypeUuid)]
+#[uuid = "3b9e6c14-7f2a-4d8b-a51e-0c6d92f4b7a3"]
+pub struct LevelCurve {
+    // total xp needed to reach level 2, 3, ... the last one is the highest level there is
+    pub xp: Vec<u32>,
+    // stat points every level up gives
+    pub gains: Stats,
+}
+
+impl LevelCurve {
+    // the level xp total xp is worth, levels start at 1
+    pub fn level_for(&self, xp: u32) -> u32 {
+        1 + self.xp.iter().take_while(|&&needed| xp >= needed).count() as u32
+    }
+
+    // total xp needed to get past level, None at the highest level
+    pub fn xp_for_next(&self, level: u32) -> Option<u32> {
+        self.xp.get(level.checked_sub(1)? as usize).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> LevelCurve {
+        LevelCurve {
+            xp: vec![50, 120, 220],
+            gains: Stats::default(),
+        }
+    }
+
+    #[test]
+    fn level_for_xp() {
+        let curve = curve();
+
+        assert_eq!(curve.level_for(0), 1);
+        assert_eq!(curve.level_for(49), 1);
+        assert_eq!(curve.level_for(50), 2);
+        assert_eq!(curve.level_for(119), 2);
+        assert_eq!(curve.level_for(120), 3);
+        assert_eq!(curve.level_for(220), 4);
+        // no level past the last one
+        assert_eq!(curve.level_for(u32::MAX), 4);
+    }
+
+    #[test]
+    fn xp_for_next_level() {
+        let curve = curve();
+
+        assert_eq!(curve.xp_for_next(0), None);
+        assert_eq!(curve.xp_for_next(1), Some(50));
+        assert_eq!(curve.xp_for_next(3), Some(220));
+        assert_eq!(curve.xp_for_next(4), None);
+    }
+
+    #[test]
+    fn levels_agree() {
+        let curve = curve();
+
+        for level in 1..4 {
+            let next = curve.xp_for_next(level).unwrap();
+            assert_eq!(curve.level_for(next - 1), level);
+            assert_eq!(curve.level_for(next), level + 1);
+        }
+    }
+}
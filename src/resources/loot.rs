@@ -0,0 +1,127 @@
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashMap;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::components::enemy::EnemyKind;
+use crate::systems::dungeon_gen::RoomKind;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DropEntry {
+    // id of an item in items.items.ron
+    pub item: String,
+    pub weight: u32,
+}
+
+/*
+Rolled rolls times, each roll has chance of dropping something and picks one of the
+entries by weight when it does
+ */
+#[derive(Clone, Debug, Deserialize)]
+pub struct DropTable {
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    pub chance: f64,
+    pub entries: Vec<DropEntry>,
+}
+
+fn default_rolls() -> u32 {
+    1
+}
+
+impl DropTable {
+    // ids of the items that dropped, the same item can drop more than once
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Vec<&str> {
+        let total: u32 = self.entries.iter().map(|entry| entry.weight).sum();
+        let mut drops = Vec::new();
+        if total == 0 {
+            return drops;
+        }
+
+        for _ in 0..self.rolls {
+            if !rng.gen_bool(self.chance.clamp(0.0, 1.0)) {
+                continue;
+            }
+            let mut pick = rng.gen_range(0..total);
+            for entry in self.entries.iter() {
+                if pick < entry.weight {
+                    drops.push(entry.item.as_str());
+                    break;
+                }
+                pick -= entry.weight;
+            }
+        }
+        drops
+    }
+}
+
+/*
+What enemies drop when they die and what lies around in rooms, loaded from assets/loot.drops.ron
+Kinds without a table drop nothing
+ */
+#[derive(Debug, Deserialize, TypeUuid)]
+#[uuid = "a4c8d61e-3f95-4b27-b0d2-7e5f1c9a8b36"]
+pub struct LootTables {
+    pub enemies: HashMap<EnemyKind, DropTable>,
+    pub rooms: HashMap<RoomKind, DropTable>,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn table(rolls: u32, chance: f64, entries: &[(&str, u32)]) -> DropTable {
+        DropTable {
+            rolls,
+            chance,
+            entries: entries
+                .iter()
+                .map(|(item, weight)| DropEntry {
+                    item: item.to_string(),
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn chance_of_dropping() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert!(table(5, 0.0, &[("coin", 1)]).roll(&mut rng).is_empty());
+        assert_eq!(table(5, 1.0, &[("coin", 1)]).roll(&mut rng), vec!["coin"; 5]);
+        // out of range chances are clamped instead of panicking
+        assert_eq!(table(2, 7.0, &[("coin", 1)]).roll(&mut rng).len(), 2);
+        assert!(table(2, -1.0, &[("coin", 1)]).roll(&mut rng).is_empty());
+    }
+
+    #[test]
+    fn nothing_to_pick() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert!(table(3, 1.0, &[]).roll(&mut rng).is_empty());
+        assert!(table(3, 1.0, &[("coin", 0)]).roll(&mut rng).is_empty());
+    }
+
+    #[test]
+    fn picks_by_weight() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let table = table(10000, 1.0, &[("common", 3), ("never", 0), ("rare", 1)]);
+        let drops = table.roll(&mut rng);
+
+        let common = drops.iter().filter(|&&item| item == "common").count();
+        let rare = drops.iter().filter(|&&item| item == "rare").count();
+        assert_eq!(common + rare, 10000);
+        assert!((7000..8000).contains(&common), "{} common drops", common);
+    }
+
+    #[test]
+    fn same_seed_same_drops() {
+        let table = table(10, 0.5, &[("a", 1), ("b", 2), ("c", 3)]);
+
+        assert_eq!(table.roll(&mut StdRng::seed_from_u64(9)), table.roll(&mut StdRng::seed_from_u64(9)));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::resources::input::InputAction;
+
+/*
+The pages of the main menu, every one is a list of rows to pick from
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MenuScreen {
+    #[default]
+    Main,
+    // picking the seed of a new game
+    NewGame,
+    Settings,
+    // rebinding the keys and buttons of every action
+    Controls,
+}
+
+/*
+What picking a row does
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    NewGame,
+    Continue,
+    Settings,
+    Quit,
+    // the seed field, typing digits fills it in whichever row is selected
+    Seed,
+    Start,
+    ToggleFullscreen,
+    ToggleVsync,
+    Controls,
+    // waits for the next key or button pressed and binds it to the action
+    Rebind(InputAction),
+    ResetControls,
+    Back,
+}
+
+impl MenuScreen {
+    pub fn actions(&self) -> &'static [MenuAction] {
+        match self {
+            MenuScreen::Main => &[MenuAction::NewGame, MenuAction::Continue, MenuAction::Settings, MenuAction::Quit],
+            MenuScreen::NewGame => &[MenuAction::Seed, MenuAction::Start, MenuAction::Back],
+            MenuScreen::Settings => &[MenuAction::ToggleFullscreen, MenuAction::ToggleVsync, MenuAction::Controls, MenuAction::Back],
+            MenuScreen::Controls => &CONTROLS_ACTIONS,
+        }
+    }
+
+    // the page Back goes to
+    pub fn back(&self) -> MenuScreen {
+        match self {
+            MenuScreen::Controls => MenuScreen::Settings,
+            _ => MenuScreen::Main,
+        }
+    }
+}
+
+// a row for every action
+const CONTROLS_ACTIONS: [MenuAction; 13] = [
+    MenuAction::Rebind(InputAction::MoveUp),
+    MenuAction::Rebind(InputAction::MoveDown),
+    MenuAction::Rebind(InputAction::MoveLeft),
+    MenuAction::Rebind(InputAction::MoveRight),
+    MenuAction::Rebind(InputAction::Run),
+    MenuAction::Rebind(InputAction::Dodge),
+    MenuAction::Rebind(InputAction::Attack),
+    MenuAction::Rebind(InputAction::Block),
+    MenuAction::Rebind(InputAction::Interact),
+    MenuAction::Rebind(InputAction::Inventory),
+    MenuAction::Rebind(InputAction::Pause),
+    MenuAction::ResetControls,
+    MenuAction::Back,
+];
+
+// longest seed that can be typed, any 19 digits still fit in a u64
+pub const MAX_SEED_DIGITS: usize = 19;
+
+/*
+Which page of the main menu is showing and which of its rows is selected
+seed is what has been typed for the next new game, left empty for a random one
+rebinding is the action waiting for a key or button to be pressed for it
+ */
+#[derive(Default)]
+pub struct MainMenuUi {
+    pub screen: MenuScreen,
+    pub selected: usize,
+    pub seed: String,
+    pub rebinding: Option<InputAction>,
+}
+
+impl MainMenuUi {
+    // go to screen with its first row selected
+    pub fn show(&mut self, screen: MenuScreen) {
+        self.screen = screen;
+        self.selected = 0;
+        self.rebinding = None;
+    }
+
+    pub fn selected_action(&self) -> Option<MenuAction> {
+        self.screen.actions().get(self.selected).copied()
+    }
+
+    // the typed seed, None when it is empty and a random one should be used
+    pub fn parsed_seed(&self) -> Option<u64> {
+        self.seed.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::input::InputBindings;
+
+    fn seed(seed: &str) -> Option<u64> {
+        MainMenuUi {
+            seed: seed.to_string(),
+            ..Default::default()
+        }
+        .parsed_seed()
+    }
+
+    #[test]
+    fn parsed_seed() {
+        assert_eq!(seed(""), None);
+        assert_eq!(seed("0"), Some(0));
+        assert_eq!(seed("00042"), Some(42));
+        assert_eq!(seed("18446744073709551615"), Some(u64::MAX));
+        assert_eq!(seed("18446744073709551616"), None);
+    }
+
+    #[test]
+    fn controls_has_a_row_for_every_action() {
+        let actions = MenuScreen::Controls.actions();
+        for action in InputBindings::default().keys.keys() {
+            assert!(actions.contains(&MenuAction::Rebind(*action)), "no row for {:?}", action);
+        }
+        assert_eq!(actions.last(), Some(&MenuAction::Back));
+        assert_eq!(MenuScreen::Controls.back(), MenuScreen::Settings);
+    }
+
+    #[test]
+    fn any_typed_seed_fits() {
+        assert!(seed(&"9".repeat(MAX_SEED_DIGITS)).is_some());
+    }
+}
@@ -1,51 +1,57 @@
 use bevy::prelude::*;
-use crate::{BoxCollider, Collision, LoadingText, MyAssets, MyStates};
-use sysinfo::{NetworkExt, NetworksExt, ProcessExt, System, SystemExt};
+use crate::LoadingText;
+use crate::components::Invulnerable;
+use sysinfo::{System, SystemExt};
 
 pub mod people;
 pub mod player;
 mod basic_setup;
 pub mod world_gen;
 pub mod dungeon_gen;
+pub mod collision;
+pub mod input;
+pub mod animation;
+pub mod ai;
+pub mod pathfinding;
+pub mod combat;
+pub mod enemy;
+pub mod projectile;
+pub mod leveling;
+pub mod loot;
+pub mod inventory;
+pub mod status;
+pub mod map;
+pub mod hud;
+pub mod main_menu;
 
+/*
+The one camera everything is drawn with, made the first time the main menu opens
+ */
 pub fn draw_begining(
     mut commands: Commands,
-    mut app_state: ResMut<State<MyStates>>,
+    cameras: Query<(), With<Camera>>,
 ) {
     //commands.spawn_bundle(Camera2dBundle::default()).insert(crate::components::camera::CameraTimer(Timer::from_seconds(0.01, true)));
 
+    if !cameras.is_empty() {
+        return;
+    }
     commands.spawn_bundle(Camera2dBundle::default());
     info!("Assets loaded, camera setup");
-
-
-    app_state.overwrite_set(MyStates::DungeonGeneration).unwrap_or_else(|e| error!("Error: {}", e));
 }
 
 /*
-Get the collisions for each collider
-
-If there is a collision, provide the entity that is colliding with the collider
+Count down invulnerability and take it away once it runs out
  */
-pub fn box_colliders(
-    mut query: Query<(&BoxCollider, Entity, &mut Collision, &Transform)>,
-    mut query2: Query<(&BoxCollider, Entity, &Transform)>,
+pub fn tick_invulnerable(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable)>,
 ) {
-    for (box_collider, entity, mut collision, transform) in query.iter_mut() {
-        let mut collisions: std::vec::Vec<u32> = collision.collisions.clone();
-        for (box_collider2, entity2, transform2) in query2.iter_mut() {
-            if entity == entity2 {
-                continue;
-            }
-            if box_collider.collides_with(&box_collider2, &transform, &transform2) {
-                if !collisions.contains(&entity2.id()) {
-                    collisions.push(entity2.id());
-                    debug!("Collision between {:?} and {:?}", entity.id(), entity2.id());
-                }
-            } else if collisions.contains(&entity2.id()) {
-                collisions.retain(|&x| x != entity2.id());
-            }
+    for (entity, mut invulnerable) in query.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
         }
-        collision.collisions = collisions;
     }
 }
 
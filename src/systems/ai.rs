@@ -0,0 +1,351 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::ai::{Ai, AiState};
+use crate::components::animation::Animator;
+use crate::components::combat::{Hitbox, Knockback, MeleeAttack, Shield};
+use crate::components::enemy::{Enemy, EnemyKind, EnemyLevel};
+use crate::components::player::PlayerAnimationState;
+use crate::components::projectile::RangedAttack;
+use crate::components::{as_collider, BoxCollider, CircleCollider, Collision, CollisionLayer, Health, LayerMask};
+use crate::events::{AnimationFinished, DeathEvent};
+use crate::systems::collision::{blocking_solids, sweep_aabb, SolidGrid};
+use crate::systems::combat::spawn_hitbox;
+use crate::systems::projectile::spawn_projectile;
+use crate::MyAssets;
+use crate::systems::dungeon_gen::Dungeon;
+
+const HITBOX_LIFETIME: f32 = 0.15;
+// close enough to a wander or last seen position to count as there
+const ARRIVE_DISTANCE: f32 = 4.0;
+// seconds spent standing around before thinking about wandering again
+const IDLE_TIME: (f32, f32) = (1.0, 3.0);
+// give up on a wander target that can't be reached after this long
+const WANDER_TIME: f32 = 4.0;
+// seconds a chasing enemy still knows where the player is after losing sight of it
+const CHASE_MEMORY: f32 = 3.0;
+
+/*
+Decide what every enemy is doing this frame
+Enemies only notice the player inside their sight range and with nothing but floor in between,
+once they do they chase, attack when close enough, or flee when they are hurt badly enough
+Losing sight of the player they keep after it for a few seconds before giving up
+ */
+pub fn update_ai(
+    time: Res<Time>,
+    dungeon: Res<Dungeon>,
+    player: Query<&Transform, With<PlayerAnimationState>>,
+    mut enemies: Query<(&Transform, &mut Ai, &Health), With<Enemy>>,
+) {
+    let player_position = player.get_single().ok().map(|transform| transform.translation.truncate());
+    let mut rng = rand::thread_rng();
+
+    for (transform, mut ai, health) in enemies.iter_mut() {
+        if ai.state == AiState::Dead {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let tuning = ai.tuning;
+        ai.state_time -= time.delta_seconds();
+
+        let seen = player_position.filter(|player| {
+            player.distance(position) <= tuning.sight_range && dungeon.line_of_sight(position, *player)
+        });
+
+        if let Some(player) = seen {
+            ai.target = Some(player);
+            if tuning.flee_health > 0.0 && health.fraction() <= tuning.flee_health {
+                ai.state = AiState::Flee;
+                ai.move_direction = (position - player).normalize_or_zero();
+            } else if player.distance(position) <= tuning.attack_range {
+                ai.state = AiState::Attack;
+                ai.move_direction = Vec2::ZERO;
+            } else {
+                ai.state = AiState::Chase;
+                ai.move_direction = (player - position).normalize_or_zero();
+            }
+            ai.state_time = CHASE_MEMORY;
+            continue;
+        }
+
+        match ai.state {
+            AiState::Chase | AiState::Wander => {
+                // a chasing enemy keeps track of the player for a moment after losing sight of it,
+                // long enough to follow it around a corner, then heads to where it was last seen
+                if ai.state == AiState::Chase && ai.state_time > 0.0 && player_position.is_some() {
+                    ai.target = player_position;
+                }
+
+                let arrived = ai.target.is_none_or(|target| target.distance(position) <= ARRIVE_DISTANCE);
+                if arrived || (ai.state == AiState::Wander && ai.state_time <= 0.0) {
+                    start_idling(&mut ai, &mut rng);
+                } else if let Some(target) = ai.target {
+                    // follow_paths steers around walls when the target isn't in a straight line
+                    ai.move_direction = (target - position).normalize_or_zero();
+                }
+            }
+            AiState::Attack | AiState::Flee => start_idling(&mut ai, &mut rng),
+            AiState::Idle => {
+                if ai.state_time > 0.0 {
+                    continue;
+                }
+
+                let target = (rng.gen::<f32>() < tuning.wander_chance)
+                    .then(|| wander_target(&dungeon, position, tuning.wander_range, &mut rng))
+                    .flatten();
+                match target {
+                    Some(target) => {
+                        ai.state = AiState::Wander;
+                        ai.target = Some(target);
+                        ai.state_time = WANDER_TIME;
+                        ai.move_direction = (target - position).normalize_or_zero();
+                    }
+                    None => start_idling(&mut ai, &mut rng),
+                }
+            }
+            AiState::Dead => {}
+        }
+    }
+}
+
+fn start_idling<R: Rng>(ai: &mut Ai, rng: &mut R) {
+    ai.state = AiState::Idle;
+    ai.target = None;
+    ai.move_direction = Vec2::ZERO;
+    ai.state_time = rng.gen_range(IDLE_TIME.0..IDLE_TIME.1);
+}
+
+/*
+Pick a floor tile near position that can be walked to in a straight line
+ */
+fn wander_target<R: Rng>(dungeon: &Dungeon, position: Vec2, range: i32, rng: &mut R) -> Option<Vec2> {
+    if range <= 0 {
+        return None;
+    }
+
+    let tile = Dungeon::world_to_tile(position);
+    // a few tries, rooms are mostly floor so this rarely misses
+    for _ in 0..4 {
+        let candidate = tile + IVec2::new(rng.gen_range(-range..=range), rng.gen_range(-range..=range));
+        let target = Dungeon::tile_to_world(candidate);
+        if !dungeon.is_wall(candidate.x, candidate.y) && dungeon.line_of_sight(position, target) {
+            return Some(target);
+        }
+    }
+
+    None
+}
+
+/*
+Enemies in the Attack state face the player and swing whenever their attack is ready
+ */
+pub fn enemy_attack(
+    mut commands: Commands,
+    mut enemies: Query<(Entity, &Transform, &Ai, &mut MeleeAttack, &mut TextureAtlasSprite), With<Enemy>>,
+) {
+    for (entity, transform, ai, mut attack, mut sprite) in enemies.iter_mut() {
+        if ai.state != AiState::Attack {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        if let Some(target) = ai.target {
+            sprite.flip_x = target.x < position.x;
+        }
+
+        if !attack.is_ready() {
+            continue;
+        }
+        attack.start();
+
+        let facing = if sprite.flip_x { -1.0 } else { 1.0 };
+        spawn_hitbox(
+            &mut commands,
+            Hitbox::new(entity, attack.damage, attack.knockback, HITBOX_LIFETIME),
+            position + Vec2::new(facing * attack.reach, 0.0),
+            attack.hitbox_size,
+            LayerMask::from_layers(&[CollisionLayer::Player]),
+        );
+    }
+}
+
+/*
+Enemies with a shield hold it up while they wait for their next swing
+ */
+pub fn enemy_block(
+    mut enemies: Query<(&Ai, &MeleeAttack, &mut Shield), With<Enemy>>,
+) {
+    for (ai, attack, mut shield) in enemies.iter_mut() {
+        let raised = ai.state == AiState::Attack && !attack.is_ready() && !attack.is_swinging();
+        if shield.raised != raised {
+            shield.raised = raised;
+        }
+    }
+}
+
+/*
+Enemies with a ranged attack shoot at the player while chasing it, as long as it is in range
+and they can see it
+ */
+#[allow(clippy::type_complexity)]
+pub fn enemy_shoot(
+    mut commands: Commands,
+    assets: Res<MyAssets>,
+    dungeon: Res<Dungeon>,
+    player: Query<&Transform, With<PlayerAnimationState>>,
+    mut enemies: Query<(Entity, &Transform, &Ai, &EnemyLevel, &mut RangedAttack, &mut TextureAtlasSprite), With<Enemy>>,
+) {
+    let player = match player.get_single() {
+        Ok(transform) => transform.translation.truncate(),
+        Err(_) => return,
+    };
+
+    for (entity, transform, ai, level, mut attack, mut sprite) in enemies.iter_mut() {
+        let position = transform.translation.truncate();
+        if ai.state != AiState::Chase
+            || !attack.is_ready()
+            || player.distance(position) > attack.range
+            || !dungeon.line_of_sight(position, player)
+        {
+            continue;
+        }
+        attack.start();
+
+        sprite.flip_x = player.x < position.x;
+        spawn_projectile(
+            &mut commands,
+            &assets,
+            attack.projectile,
+            entity,
+            position,
+            player,
+            LayerMask::from_layers(&[CollisionLayer::Player]),
+            level.damage_multiplier,
+        );
+    }
+}
+
+/*
+Move enemies the way update_ai wants them to go, swept against solids like the player is
+Knocked back enemies are left to apply_knockback
+ */
+#[allow(clippy::type_complexity)]
+pub fn move_enemies(
+    time: Res<Time>,
+    enemies: Query<(Entity, &Ai), (With<Enemy>, Without<Knockback>)>,
+    mut sprites: Query<&mut TextureAtlasSprite, With<Enemy>>,
+    mut transforms: ParamSet<(
+        Query<(Entity, Option<&BoxCollider>, Option<&CircleCollider>, &Transform)>,
+        Query<&mut Transform, With<Enemy>>,
+    )>,
+    mut solid_grid: ResMut<SolidGrid>,
+) {
+    let mut moves = Vec::new();
+
+    let colliders = transforms.p0();
+    for (entity, ai) in enemies.iter() {
+        if ai.move_direction == Vec2::ZERO || ai.state == AiState::Dead {
+            continue;
+        }
+
+        let delta = ai.move_direction * ai.tuning.speed * time.delta_seconds();
+        let delta = match colliders.get(entity) {
+            Ok((_, box_collider, circle_collider, transform)) => match as_collider(box_collider, circle_collider) {
+                Some(collider) => {
+                    let (min, max) = collider.shape(transform).bounds();
+                    let others = solid_grid
+                        .near(min, max, delta)
+                        .filter(|other| *other != entity)
+                        .filter_map(|other| colliders.get(other).ok())
+                        .map(|(_, box_collider, circle_collider, transform)| (box_collider, circle_collider, transform));
+                    let solids = blocking_solids(collider, others);
+                    sweep_aabb(min, max, delta, &solids)
+                }
+                None => delta,
+            },
+            Err(_) => continue,
+        };
+        moves.push((entity, delta));
+    }
+
+    let mut transforms = transforms.p1();
+    for (entity, delta) in moves {
+        if let Ok(mut transform) = transforms.get_mut(entity) {
+            transform.translation += delta.extend(0.0);
+        }
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            if delta.x < 0.0 {
+                sprite.flip_x = true;
+            } else if delta.x > 0.0 {
+                sprite.flip_x = false;
+            }
+        }
+    }
+}
+
+/*
+Play the clip for what each enemy is doing
+ */
+#[allow(clippy::type_complexity)]
+pub fn animate_enemies(
+    mut enemies: Query<(&Ai, &EnemyKind, &MeleeAttack, Option<&RangedAttack>, Option<&Shield>, Option<&Knockback>, &mut Animator), With<Enemy>>,
+) {
+    for (ai, kind, attack, ranged_attack, shield, knockback, mut animator) in enemies.iter_mut() {
+        let clip = if ai.state == AiState::Dead {
+            "death"
+        } else if knockback.is_some() {
+            "take_hit"
+        } else if attack.is_swinging() {
+            "attack"
+        } else if shield.is_some_and(|shield| shield.raised) {
+            "shield"
+        } else if ranged_attack.is_some_and(|attack| attack.is_swinging()) {
+            "attack3"
+        } else if ai.move_direction != Vec2::ZERO {
+            kind.move_clip()
+        } else {
+            kind.idle_clip()
+        };
+        animator.play(clip);
+    }
+}
+
+/*
+Dead enemies stop taking part in collisions and stay around until their death clip is over
+ */
+pub fn kill_enemies(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    mut enemies: Query<&mut Ai, With<Enemy>>,
+) {
+    for death in deaths.iter() {
+        if let Ok(mut ai) = enemies.get_mut(death.entity) {
+            ai.state = AiState::Dead;
+            ai.move_direction = Vec2::ZERO;
+            commands
+                .entity(death.entity)
+                .remove::<BoxCollider>()
+                .remove::<CircleCollider>()
+                .remove::<Collision>()
+                .remove::<Knockback>();
+        }
+    }
+}
+
+pub fn despawn_dead_enemies(
+    mut commands: Commands,
+    mut animations_finished: EventReader<AnimationFinished>,
+    enemies: Query<&Ai, With<Enemy>>,
+) {
+    for finished in animations_finished.iter() {
+        if finished.clip != "death" {
+            continue;
+        }
+        if let Ok(ai) = enemies.get(finished.entity) {
+            if ai.state == AiState::Dead {
+                commands.entity(finished.entity).despawn_recursive();
+            }
+        }
+    }
+}
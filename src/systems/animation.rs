@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::components::animation::Animator;
+use crate::events::AnimationFinished;
+use crate::resources::animation::AnimationLibrary;
+use crate::MyAssets;
+
+/*
+Step every Animator through its clip and show the current frame
+Switching to a clip that lives on another atlas swaps the sprite's atlas as well
+ */
+pub fn animate_sprites(
+    time: Res<Time>,
+    assets: Res<MyAssets>,
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut query: Query<(Entity, &mut Animator, &mut TextureAtlasSprite, &mut Handle<TextureAtlas>)>,
+    mut animation_finished: EventWriter<AnimationFinished>,
+) {
+    let library = match libraries.get(&assets.animations) {
+        Some(library) => library,
+        None => return,
+    };
+
+    for (entity, mut animator, mut sprite, mut atlas) in query.iter_mut() {
+        let clip = match library.clip(&animator.set, &animator.clip) {
+            Some(clip) => clip,
+            None => {
+                warn!("No animation {}/{}", animator.set, animator.clip);
+                continue;
+            }
+        };
+
+        if animator.restarted {
+            animator.restarted = false;
+            animator.timer.set_duration(std::time::Duration::from_secs_f32(1.0 / clip.fps));
+            animator.timer.reset();
+
+            match assets.atlas(&clip.atlas) {
+                Some(handle) if *atlas != *handle => *atlas = handle.clone(),
+                Some(_) => {}
+                None => warn!("No atlas named {} for animation {}/{}", clip.atlas, animator.set, animator.clip),
+            }
+        } else if !animator.finished {
+            animator.timer.tick(time.delta());
+
+            for _ in 0..animator.timer.times_finished_this_tick() {
+                if animator.frame + 1 < clip.frame_count() {
+                    animator.frame += 1;
+                } else if clip.looping {
+                    animator.frame = 0;
+                } else {
+                    animator.finished = true;
+                    animation_finished.send(AnimationFinished {
+                        entity,
+                        clip: animator.clip.clone(),
+                    });
+                    break;
+                }
+            }
+        }
+
+        sprite.index = clip.first + animator.frame;
+    }
+}
@@ -0,0 +1,602 @@
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use crate::components::{as_collider, BoxCollider, CircleCollider, Collider, ColliderShape, ColliderType, Collision};
+use crate::events::{CollisionEnded, CollisionStarted, TriggerEntered, TriggerExited};
+use crate::resources::{CHUNK_SIZE, TILE_SIZE};
+
+/*
+Broad phase for the collision system
+Colliders are bucketed into a uniform grid of chunk sized cells, so each collider only
+has to be tested against the colliders sharing a cell with it instead of every other one
+ */
+pub struct SpatialGrid {
+    cell_size: Vec2,
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(Vec2::new(
+            CHUNK_SIZE.x as f32 * TILE_SIZE.x,
+            CHUNK_SIZE.y as f32 * TILE_SIZE.y,
+        ))
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: Vec2) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /*
+    Empty every cell but keep their allocations around for the next frame
+     */
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    fn cell_range(&self, min: Vec2, max: Vec2) -> (IVec2, IVec2) {
+        (
+            (min / self.cell_size).floor().as_ivec2(),
+            (max / self.cell_size).floor().as_ivec2(),
+        )
+    }
+
+    pub fn insert(&mut self, index: usize, min: Vec2, max: Vec2) {
+        let (min_cell, max_cell) = self.cell_range(min, max);
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    /*
+    Fill out with every index that shares a cell with the given bounds
+    These are only candidates, the narrow phase still has to check them
+     */
+    pub fn query(&self, min: Vec2, max: Vec2, out: &mut Vec<usize>) {
+        out.clear();
+
+        let (min_cell, max_cell) = self.cell_range(min, max);
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                if let Some(cell) = self.cells.get(&IVec2::new(x, y)) {
+                    out.extend_from_slice(cell);
+                }
+            }
+        }
+
+        // big colliders live in several cells
+        out.sort_unstable();
+        out.dedup();
+    }
+}
+
+/*
+Every solid collider bucketed by where it was at the start of the frame, shared by everything
+that moves so a sweep only has to look at the solids around it instead of all of them
+ */
+#[derive(Default)]
+pub struct SolidGrid {
+    grid: SpatialGrid,
+    entities: Vec<Entity>,
+    candidates: Vec<usize>,
+}
+
+// things moved earlier in the frame may be a step away from where the grid has them
+const SOLID_GRID_MARGIN: f32 = TILE_SIZE.x;
+
+impl SolidGrid {
+    /*
+    Get every solid that could be in the way of a box moving by delta
+    These are only candidates, blocking_solids still checks them
+     */
+    pub fn near(&mut self, min: Vec2, max: Vec2, delta: Vec2) -> impl Iterator<Item = Entity> + '_ {
+        let margin = Vec2::splat(SOLID_GRID_MARGIN);
+        self.grid.query(min.min(min + delta) - margin, max.max(max + delta) + margin, &mut self.candidates);
+        self.candidates.iter().map(|&index| self.entities[index])
+    }
+}
+
+/*
+Rebuild the SolidGrid before anything moves
+Triggers never block anything so they are left out
+ */
+#[allow(clippy::type_complexity)]
+pub fn update_solid_grid(
+    mut solid_grid: ResMut<SolidGrid>,
+    query: Query<(Entity, &Transform, Option<&BoxCollider>, Option<&CircleCollider>), Or<(With<BoxCollider>, With<CircleCollider>)>>,
+) {
+    let solid_grid = &mut *solid_grid;
+    solid_grid.grid.clear();
+    solid_grid.entities.clear();
+
+    for (entity, transform, box_collider, circle_collider) in query.iter() {
+        let collider = match as_collider(box_collider, circle_collider) {
+            Some(collider) if *collider.collider_type() == ColliderType::Solid => collider,
+            _ => continue,
+        };
+        let (min, max) = collider.shape(transform).bounds();
+        solid_grid.grid.insert(solid_grid.entities.len(), min, max);
+        solid_grid.entities.push(entity);
+    }
+}
+
+// gap left between a collider and the solid it was stopped by
+const SKIN: f32 = 0.01;
+
+/*
+Sweep a box by delta, one axis at a time, against the bounds of solid colliders
+Returns how far the box can actually move, x is resolved first and y is swept from there,
+so a box blocked on one axis still slides along the other
+ */
+pub fn sweep_aabb(min: Vec2, max: Vec2, delta: Vec2, solids: &[(Vec2, Vec2)]) -> Vec2 {
+    let dx = sweep_axis(min, max, delta.x, 0, solids);
+    let moved = Vec2::new(dx, 0.0);
+    let dy = sweep_axis(min + moved, max + moved, delta.y, 1, solids);
+    Vec2::new(dx, dy)
+}
+
+fn sweep_axis(min: Vec2, max: Vec2, delta: f32, axis: usize, solids: &[(Vec2, Vec2)]) -> f32 {
+    let other_axis = 1 - axis;
+    let mut delta = delta;
+
+    for (solid_min, solid_max) in solids {
+        // only solids level with the box on the other axis can be run into
+        if max[other_axis] <= solid_min[other_axis] || min[other_axis] >= solid_max[other_axis] {
+            continue;
+        }
+
+        if delta > 0.0 && solid_min[axis] >= max[axis] - SKIN {
+            delta = delta.min(solid_min[axis] - max[axis] - SKIN).max(0.0);
+        } else if delta < 0.0 && solid_max[axis] <= min[axis] + SKIN {
+            delta = delta.max(solid_max[axis] - min[axis] + SKIN).min(0.0);
+        }
+    }
+
+    delta
+}
+
+/*
+Get the bounds of every collider that blocks collider, to sweep and depenetrate against
+others are usually the ones SolidGrid::near found
+Circles are treated as their bounding box
+ */
+pub fn blocking_solids<'a>(
+    collider: &dyn Collider,
+    others: impl IntoIterator<Item = (Option<&'a BoxCollider>, Option<&'a CircleCollider>, &'a Transform)>,
+) -> Vec<(Vec2, Vec2)> {
+    others
+        .into_iter()
+        .filter_map(|(box_collider, circle_collider, transform)| {
+            let other = as_collider(box_collider, circle_collider)?;
+            collider
+                .is_blocked_by(other)
+                .then(|| other.shape(transform).bounds())
+        })
+        .collect()
+}
+
+/*
+Get the offset that pushes a box out of every solid it overlaps
+Each overlap is resolved along the axis it penetrates the least
+ */
+pub fn depenetrate(min: Vec2, max: Vec2, solids: &[(Vec2, Vec2)]) -> Vec2 {
+    let mut push = Vec2::ZERO;
+
+    for (solid_min, solid_max) in solids {
+        let (min, max) = (min + push, max + push);
+        let overlap = max.min(*solid_max) - min.max(*solid_min);
+        if overlap.x <= 0.0 || overlap.y <= 0.0 {
+            continue;
+        }
+
+        let center = (min + max) / 2.0;
+        let solid_center = (*solid_min + *solid_max) / 2.0;
+        if overlap.x < overlap.y {
+            push.x += if center.x < solid_center.x { -overlap.x } else { overlap.x };
+        } else {
+            push.y += if center.y < solid_center.y { -overlap.y } else { overlap.y };
+        }
+    }
+
+    push
+}
+
+/*
+Get the collisions for each collider, box or circle
+
+If there is a collision, provide the entity that is colliding with the collider
+Entering and leaving a collision is also sent as an event, overlaps involving a trigger
+are sent as TriggerEntered/TriggerExited instead of CollisionStarted/CollisionEnded
+
+A trigger overlap is sent once, by the trigger when it has a Collision of its own and by the
+other entity when it doesn't. Trigger pairs are remembered as (trigger, other) when they start,
+so the pair still ends as a trigger after the trigger is despawned
+ */
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn detect_collisions(
+    mut query: Query<(Entity, &mut Collision)>,
+    has_collision: Query<(), With<Collision>>,
+    query2: Query<(Entity, &Transform, Option<&BoxCollider>, Option<&CircleCollider>), Or<(With<BoxCollider>, With<CircleCollider>)>>,
+    mut grid: Local<SpatialGrid>,
+    mut candidates: Local<Vec<usize>>,
+    mut touching: Local<Vec<Entity>>,
+    mut trigger_pairs: Local<HashSet<(Entity, Entity)>>,
+    mut collision_started: EventWriter<CollisionStarted>,
+    mut collision_ended: EventWriter<CollisionEnded>,
+    mut trigger_entered: EventWriter<TriggerEntered>,
+    mut trigger_exited: EventWriter<TriggerExited>,
+) {
+    let colliders: Vec<(Entity, &dyn Collider, ColliderShape)> = query2
+        .iter()
+        .filter_map(|(entity, transform, box_collider, circle_collider)| {
+            let collider = as_collider(box_collider, circle_collider)?;
+            Some((entity, collider, collider.shape(transform)))
+        })
+        .collect();
+    let indices: HashMap<Entity, usize> = colliders
+        .iter()
+        .enumerate()
+        .map(|(index, (entity, _, _))| (*entity, index))
+        .collect();
+
+    grid.clear();
+    for (index, (_, _, shape)) in colliders.iter().enumerate() {
+        let (min, max) = shape.bounds();
+        grid.insert(index, min, max);
+    }
+
+    let mut ended = Vec::new();
+    for (entity, mut collision) in query.iter_mut() {
+        touching.clear();
+
+        // an entity without a collider can't be touching anything
+        let own = indices.get(&entity).map(|&index| colliders[index]);
+        if let Some((_, collider, shape)) = own {
+            let (min, max) = shape.bounds();
+            grid.query(min, max, &mut candidates);
+
+            for &index in candidates.iter() {
+                let (entity2, collider2, shape2) = colliders[index];
+                if entity == entity2 {
+                    continue;
+                }
+                if collider.interacts_with(collider2) && shape.overlaps(&shape2) {
+                    touching.push(entity2);
+                }
+            }
+        }
+
+        // whichever side of a trigger pair sends its events
+        let sends = |(trigger, other): (Entity, Entity)| {
+            let sender = if has_collision.contains(trigger) { trigger } else { other };
+            sender == entity
+        };
+
+        for &other in collision.collisions.iter() {
+            if touching.contains(&other) {
+                continue;
+            }
+            let pair = [(entity, other), (other, entity)]
+                .into_iter()
+                .find(|pair| trigger_pairs.contains(pair));
+            match pair {
+                Some(pair) if sends(pair) => {
+                    // the other side may still have to look it up this frame
+                    ended.push(pair);
+                    trigger_exited.send(TriggerExited {
+                        trigger: pair.0,
+                        other: pair.1,
+                    });
+                }
+                Some(_) => {}
+                None => collision_ended.send(CollisionEnded { entity, other }),
+            }
+        }
+        collision.collisions.retain(|other| touching.contains(other));
+
+        let own_trigger = own.is_some_and(|(_, collider, _)| *collider.collider_type() == ColliderType::Trigger);
+        for &other in touching.iter() {
+            if collision.collisions.contains(&other) {
+                continue;
+            }
+            collision.collisions.push(other);
+            debug!("Collision between {:?} and {:?}", entity, other);
+
+            // everything touching has a collider this frame
+            let other_trigger = *colliders[indices[&other]].1.collider_type() == ColliderType::Trigger;
+            let pair = match (own_trigger, other_trigger) {
+                // two triggers are keyed the same way from both sides
+                (true, true) => Some((entity.min(other), entity.max(other))),
+                (true, false) => Some((entity, other)),
+                (false, true) => Some((other, entity)),
+                (false, false) => None,
+            };
+            match pair {
+                Some(pair) if sends(pair) => {
+                    trigger_pairs.insert(pair);
+                    trigger_entered.send(TriggerEntered {
+                        trigger: pair.0,
+                        other: pair.1,
+                    });
+                }
+                Some(_) => {}
+                None => collision_started.send(CollisionStarted { entity, other }),
+            }
+        }
+    }
+
+    // nothing is left to end pairs where both sides are gone
+    trigger_pairs.retain(|pair| !ended.contains(pair) && (has_collision.contains(pair.0) || has_collision.contains(pair.1)));
+}
+
+/*
+Log every collision and trigger overlap as it starts and ends, to see what touched what
+ */
+pub fn log_collisions(
+    mut collision_started: EventReader<CollisionStarted>,
+    mut collision_ended: EventReader<CollisionEnded>,
+    mut trigger_entered: EventReader<TriggerEntered>,
+    mut trigger_exited: EventReader<TriggerExited>,
+) {
+    for event in collision_started.iter() {
+        debug!("{:?} started touching {:?}", event.entity, event.other);
+    }
+    for event in collision_ended.iter() {
+        debug!("{:?} stopped touching {:?}", event.entity, event.other);
+    }
+    for event in trigger_entered.iter() {
+        debug!("{:?} entered trigger {:?}", event.other, event.trigger);
+    }
+    for event in trigger_exited.iter() {
+        debug!("{:?} left trigger {:?}", event.other, event.trigger);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::time::Instant;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::components::{CollisionLayer, LayerMask};
+
+    /*
+    Scatter count colliders over a 128x128 tile map like the dungeon, half boxes and half circles
+    Sizes and layers are mixed so some pairs overlap without interacting
+     */
+    fn scatter(world: &mut World, count: u32, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let map_size = 128.0 * TILE_SIZE.x;
+        let layers = [CollisionLayer::Player, CollisionLayer::Enemy, CollisionLayer::Projectile];
+
+        for i in 0..count {
+            let position = Vec2::new(rng.gen_range(0.0..map_size), rng.gen_range(0.0..map_size));
+            let membership = LayerMask::from_layers(&[layers[rng.gen_range(0..layers.len())]]);
+            let collision_mask = if rng.gen_bool(0.8) { LayerMask::ALL } else { LayerMask::from_layers(&[CollisionLayer::Player]) };
+            // a few big ones that span several grid cells
+            let size = if rng.gen_bool(0.02) { rng.gen_range(200.0..600.0) } else { rng.gen_range(8.0..60.0) };
+
+            let mut entity = world.spawn();
+            entity
+                .insert(Collision {
+                    collisions: Vec::new(),
+                })
+                .insert(Transform::from_translation(position.extend(0.0)));
+
+            if i % 2 == 0 {
+                entity.insert(BoxCollider {
+                    width: size,
+                    height: size * rng.gen_range(0.5..2.0),
+                    membership,
+                    collision_mask,
+                    offset: Vec2::new(rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0)),
+                    scale: Vec2::splat(rng.gen_range(0.5..1.5)),
+                    collider_type: ColliderType::Solid,
+                });
+            } else {
+                entity.insert(CircleCollider {
+                    radius: size / 2.0,
+                    membership,
+                    collision_mask,
+                    offset: Vec2::ZERO,
+                    collider_type: ColliderType::Solid,
+                });
+            }
+        }
+
+        world.init_resource::<Events<CollisionStarted>>();
+        world.init_resource::<Events<CollisionEnded>>();
+        world.init_resource::<Events<TriggerEntered>>();
+        world.init_resource::<Events<TriggerExited>>();
+    }
+
+    #[test]
+    fn broad_phase_matches_brute_force() {
+        let mut world = World::new();
+        scatter(&mut world, 4000, 7);
+        SystemStage::single(detect_collisions).run(&mut world);
+
+        let mut query = world.query::<(Entity, &Transform, Option<&BoxCollider>, Option<&CircleCollider>, &Collision)>();
+        let colliders: Vec<_> = query
+            .iter(&world)
+            .filter_map(|(entity, transform, box_collider, circle_collider, collision)| {
+                let collider = as_collider(box_collider, circle_collider)?;
+                Some((entity, collider, collider.shape(transform), collision))
+            })
+            .collect();
+
+        let mut pairs = 0;
+        for (entity, collider, shape, collision) in colliders.iter() {
+            let mut expected: Vec<Entity> = colliders
+                .iter()
+                .filter(|(other, other_collider, other_shape, _)| {
+                    other != entity && collider.interacts_with(*other_collider) && shape.overlaps(other_shape)
+                })
+                .map(|(other, ..)| *other)
+                .collect();
+            let mut found = collision.collisions.clone();
+            expected.sort();
+            found.sort();
+
+            assert_eq!(found, expected, "collisions of {:?}", entity);
+            pairs += found.len();
+        }
+        assert!(pairs > 0, "nothing overlapped, the test says nothing");
+    }
+
+    #[test]
+    fn solid_grid_finds_every_solid_in_the_way() {
+        let mut world = World::new();
+        scatter(&mut world, 2000, 3);
+        world.init_resource::<SolidGrid>();
+        SystemStage::single(update_solid_grid).run(&mut world);
+
+        let mut query = world.query::<(Entity, &Transform, Option<&BoxCollider>, Option<&CircleCollider>)>();
+        let solids: Vec<_> = query
+            .iter(&world)
+            .filter_map(|(entity, transform, box_collider, circle_collider)| {
+                let collider = as_collider(box_collider, circle_collider)?;
+                Some((entity, collider.shape(transform).bounds()))
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut solid_grid = world.resource_mut::<SolidGrid>();
+        for _ in 0..200 {
+            let min = Vec2::new(rng.gen_range(0.0..4000.0), rng.gen_range(0.0..4000.0));
+            let max = min + Vec2::splat(rng.gen_range(8.0..40.0));
+            let delta = Vec2::new(rng.gen_range(-60.0..60.0), rng.gen_range(-60.0..60.0));
+            let (swept_min, swept_max) = (min.min(min + delta), max.max(max + delta));
+
+            let near: Vec<Entity> = solid_grid.near(min, max, delta).collect();
+            for (entity, (solid_min, solid_max)) in solids.iter() {
+                let in_the_way = solid_min.x < swept_max.x && solid_max.x > swept_min.x && solid_min.y < swept_max.y && solid_max.y > swept_min.y;
+                assert!(!in_the_way || near.contains(entity), "{:?} is in the way but wasn't found", entity);
+            }
+        }
+    }
+
+    fn spawn_collider(world: &mut World, x: f32, collider_type: ColliderType) -> Entity {
+        world
+            .spawn()
+            .insert(Collision {
+                collisions: Vec::new(),
+            })
+            .insert(Transform::from_xyz(x, 0.0, 0.0))
+            .insert(CircleCollider {
+                radius: 10.0,
+                membership: LayerMask::ALL,
+                collision_mask: LayerMask::ALL,
+                offset: Vec2::ZERO,
+                collider_type,
+            })
+            .id()
+    }
+
+    fn drain<T: Send + Sync + 'static>(world: &mut World) -> Vec<T> {
+        world.resource_mut::<Events<T>>().drain().collect()
+    }
+
+    #[test]
+    fn trigger_events_are_sent_once() {
+        let mut world = World::new();
+        scatter(&mut world, 0, 0);
+        let mut stage = SystemStage::single(detect_collisions);
+        let trigger = spawn_collider(&mut world, 0.0, ColliderType::Trigger);
+        let other = spawn_collider(&mut world, 5.0, ColliderType::Solid);
+
+        stage.run(&mut world);
+        let entered = drain::<TriggerEntered>(&mut world);
+        assert_eq!(entered.len(), 1);
+        assert_eq!((entered[0].trigger, entered[0].other), (trigger, other));
+        assert!(drain::<CollisionStarted>(&mut world).is_empty());
+
+        world.entity_mut(other).get_mut::<Transform>().unwrap().translation.x = 100.0;
+        stage.run(&mut world);
+        let exited = drain::<TriggerExited>(&mut world);
+        assert_eq!(exited.len(), 1);
+        assert_eq!((exited[0].trigger, exited[0].other), (trigger, other));
+        assert!(drain::<CollisionEnded>(&mut world).is_empty());
+    }
+
+    #[test]
+    fn despawned_trigger_still_exits_as_a_trigger() {
+        let mut world = World::new();
+        scatter(&mut world, 0, 0);
+        let mut stage = SystemStage::single(detect_collisions);
+        let trigger = spawn_collider(&mut world, 0.0, ColliderType::Trigger);
+        let other = spawn_collider(&mut world, 5.0, ColliderType::Solid);
+
+        stage.run(&mut world);
+        assert_eq!(drain::<TriggerEntered>(&mut world).len(), 1);
+
+        world.despawn(trigger);
+        stage.run(&mut world);
+        let exited = drain::<TriggerExited>(&mut world);
+        assert_eq!(exited.len(), 1);
+        assert_eq!((exited[0].trigger, exited[0].other), (trigger, other));
+        assert!(drain::<CollisionEnded>(&mut world).is_empty());
+    }
+
+    #[test]
+    fn solid_pairs_are_sent_from_both_sides() {
+        let mut world = World::new();
+        scatter(&mut world, 0, 0);
+        let mut stage = SystemStage::single(detect_collisions);
+        let first = spawn_collider(&mut world, 0.0, ColliderType::Solid);
+        let second = spawn_collider(&mut world, 5.0, ColliderType::Solid);
+
+        stage.run(&mut world);
+        let mut started: Vec<_> = drain::<CollisionStarted>(&mut world).iter().map(|event| (event.entity, event.other)).collect();
+        started.sort();
+        let mut expected = vec![(first, second), (second, first)];
+        expected.sort();
+        assert_eq!(started, expected);
+        assert!(drain::<TriggerEntered>(&mut world).is_empty());
+    }
+
+    // cargo test collision_bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn collision_bench() {
+        let runs = 10;
+
+        for count in [1000, 5000, 10000, 20000] {
+            let mut world = World::new();
+            scatter(&mut world, count, 1);
+            let mut stage = SystemStage::single(detect_collisions);
+
+            let mut run_times: Vec<u128> = (0..runs)
+                .map(|_| {
+                    let start_time = Instant::now();
+                    stage.run(&mut world);
+                    start_time.elapsed().as_micros()
+                })
+                .collect();
+            run_times.sort();
+
+            writeln!(
+                std::io::stderr(),
+                "{} colliders: average {}us, median {}us, min {}us, max {}us",
+                count,
+                run_times.iter().sum::<u128>() / runs as u128,
+                run_times[run_times.len() / 2],
+                run_times[0],
+                run_times[run_times.len() - 1],
+            )
+            .unwrap();
+        }
+    }
+}
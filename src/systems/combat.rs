@@ -0,0 +1,276 @@
+use bevy::prelude::*;
+
+use crate::components::{as_collider, BoxCollider, CircleCollider, ColliderType, Collision, CollisionLayer, FloorEntity, Health, Invulnerable, LayerMask};
+use crate::components::combat::{Hitbox, Knockback, MeleeAttack, Shield};
+use crate::components::player::PlayerAnimationState;
+use crate::components::stats::Stats;
+use crate::components::projectile::RangedAttack;
+use crate::events::{DamageEvent, DeathEvent, HitEvent, TriggerEntered};
+use crate::resources::input::{ActionInput, InputAction};
+use crate::resources::inventory::InventoryUi;
+use crate::systems::collision::{blocking_solids, sweep_aabb, SolidGrid};
+
+// seconds a target can't be hurt again after taking damage
+const HIT_INVULNERABILITY: f32 = 0.4;
+const KNOCKBACK_TIME: f32 = 0.2;
+const HITBOX_LIFETIME: f32 = 0.15;
+
+pub fn tick_attacks(
+    time: Res<Time>,
+    mut melee: Query<&mut MeleeAttack>,
+    mut ranged: Query<&mut RangedAttack>,
+) {
+    for mut attack in melee.iter_mut() {
+        attack.swing.tick(time.delta());
+        attack.cooldown.tick(time.delta());
+    }
+    for mut attack in ranged.iter_mut() {
+        attack.swing.tick(time.delta());
+        attack.cooldown.tick(time.delta());
+    }
+}
+
+/*
+Swing at whatever is in front of the player when attack is pressed
+The hitbox goes on the side the sprite is facing, Strength adds to the damage
+ */
+#[allow(clippy::type_complexity)]
+pub fn player_attack(
+    mut commands: Commands,
+    actions: Res<ActionInput>,
+    inventory_ui: Res<InventoryUi>,
+    mut query: Query<(Entity, &Transform, &TextureAtlasSprite, &mut MeleeAttack, Option<&Shield>, Option<&Stats>), With<PlayerAnimationState>>,
+) {
+    if inventory_ui.open {
+        return;
+    }
+    for (entity, transform, sprite, mut attack, shield, stats) in query.iter_mut() {
+        let blocking = shield.is_some_and(|shield| shield.raised);
+        if !actions.just_pressed(InputAction::Attack) || !attack.is_ready() || blocking {
+            continue;
+        }
+        attack.start();
+
+        let facing = if sprite.flip_x { -1.0 } else { 1.0 };
+        let position = transform.translation.truncate() + Vec2::new(facing * attack.reach, 0.0);
+        let damage = attack.damage + stats.map_or(0.0, Stats::damage_bonus);
+        spawn_hitbox(
+            &mut commands,
+            Hitbox::new(entity, damage, attack.knockback, HITBOX_LIFETIME),
+            position,
+            attack.hitbox_size,
+            LayerMask::from_layers(&[CollisionLayer::Enemy]),
+        );
+    }
+}
+
+pub fn spawn_hitbox(commands: &mut Commands, hitbox: Hitbox, position: Vec2, size: Vec2, targets: LayerMask) {
+    commands
+        .spawn()
+        .insert(hitbox)
+        .insert(FloorEntity)
+        .insert(BoxCollider {
+            width: size.x,
+            height: size.y,
+            membership: LayerMask::from_layers(&[CollisionLayer::Trigger]),
+            collision_mask: targets,
+            offset: Vec2::ZERO,
+            scale: Vec2::ONE,
+            collider_type: ColliderType::Trigger,
+        })
+        .insert(Collision {
+            collisions: Vec::new(),
+        })
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))));
+}
+
+/*
+Turn hitbox overlaps into hits, pushing targets away from the hitbox owner
+ */
+pub fn hitbox_hits(
+    mut trigger_entered: EventReader<TriggerEntered>,
+    mut hitboxes: Query<(&mut Hitbox, &Transform)>,
+    targets: Query<&Transform, With<Health>>,
+    mut hits: EventWriter<HitEvent>,
+) {
+    for event in trigger_entered.iter() {
+        let (mut hitbox, hitbox_transform) = match hitboxes.get_mut(event.trigger) {
+            Ok(hitbox) => hitbox,
+            Err(_) => continue,
+        };
+        if event.other == hitbox.owner || hitbox.hit.contains(&event.other) {
+            continue;
+        }
+        let target_transform = match targets.get(event.other) {
+            Ok(transform) => transform,
+            Err(_) => continue,
+        };
+
+        hitbox.hit.push(event.other);
+        let direction = (target_transform.translation - hitbox_transform.translation)
+            .truncate()
+            .normalize_or_zero();
+        hits.send(HitEvent {
+            target: event.other,
+            source: hitbox.owner,
+            damage: hitbox.damage,
+            knockback: direction * hitbox.knockback,
+        });
+    }
+}
+
+pub fn expire_hitboxes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Hitbox)>,
+) {
+    for (entity, mut hitbox) in query.iter_mut() {
+        if hitbox.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/*
+Apply hits to Health
+Invulnerable and already dead targets ignore the hit, a raised shield facing the attacker
+blocks some or all of it, everything else takes the damage after armor, gets knocked back
+and can't be hurt again for a moment
+ */
+#[allow(clippy::type_complexity)]
+pub fn apply_hits(
+    mut commands: Commands,
+    mut hits: EventReader<HitEvent>,
+    mut query: Query<(&mut Health, &Transform, Option<&Shield>, Option<&TextureAtlasSprite>), Without<Invulnerable>>,
+    sources: Query<&Transform>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+) {
+    for hit in hits.iter() {
+        let (mut health, transform, shield, sprite) = match query.get_mut(hit.target) {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        if health.is_dead() {
+            continue;
+        }
+
+        // the hit comes from the opposite way it pushes, or from wherever the source is
+        let direction = if hit.knockback != Vec2::ZERO {
+            -hit.knockback.normalize()
+        } else {
+            sources
+                .get(hit.source)
+                .map(|source| (source.translation - transform.translation).truncate().normalize_or_zero())
+                .unwrap_or_default()
+        };
+        let facing = if sprite.is_some_and(|sprite| sprite.flip_x) { Vec2::NEG_X } else { Vec2::X };
+
+        let (damage, bonus_armor, knockback, blocked) = match shield {
+            Some(shield) if shield.covers(facing, direction) => (
+                hit.damage * (1.0 - shield.block),
+                shield.armor,
+                hit.knockback * (1.0 - shield.block),
+                true,
+            ),
+            _ => (hit.damage, 0.0, hit.knockback, false),
+        };
+
+        // the shield soaked up all of it, no damage and no invulnerability
+        if blocked && damage <= health.armor + bonus_armor {
+            damage_events.send(DamageEvent {
+                target: hit.target,
+                source: hit.source,
+                amount: 0.0,
+                blocked,
+            });
+            if knockback != Vec2::ZERO {
+                commands.entity(hit.target).insert(Knockback::new(knockback, KNOCKBACK_TIME));
+            }
+            continue;
+        }
+
+        let amount = health.take_damage(damage, bonus_armor);
+        damage_events.send(DamageEvent {
+            target: hit.target,
+            source: hit.source,
+            amount,
+            blocked,
+        });
+
+        if health.is_dead() {
+            death_events.send(DeathEvent {
+                entity: hit.target,
+                killer: hit.source,
+            });
+            continue;
+        }
+
+        let mut target = commands.entity(hit.target);
+        target.insert(Invulnerable(Timer::from_seconds(HIT_INVULNERABILITY, false)));
+        if knockback != Vec2::ZERO {
+            target.insert(Knockback::new(knockback, KNOCKBACK_TIME));
+        }
+    }
+}
+
+/*
+Log all the damage dealt, from hits and from effects like poison alike
+ */
+pub fn log_damage(mut damage_events: EventReader<DamageEvent>) {
+    for event in damage_events.iter() {
+        match event.blocked {
+            true if event.amount <= 0.0 => debug!("{:?} blocked a hit from {:?}", event.target, event.source),
+            true => debug!("{:?} took {} from {:?} through a block", event.target, event.amount, event.source),
+            false => debug!("{:?} took {} from {:?}", event.target, event.amount, event.source),
+        }
+    }
+}
+
+/*
+Hold the shield up while block is held, the player can't swing and block at once
+ */
+pub fn player_block(
+    actions: Res<ActionInput>,
+    mut query: Query<(&mut Shield, Option<&MeleeAttack>), With<PlayerAnimationState>>,
+) {
+    for (mut shield, attack) in query.iter_mut() {
+        let raised = actions.pressed(InputAction::Block) && !attack.is_some_and(|attack| attack.is_swinging());
+        // only touch the shield when it changes, so Changed<Shield> means something
+        if shield.raised != raised {
+            shield.raised = raised;
+        }
+    }
+}
+
+/*
+Push knocked back entities, slowing down to a stop, without going through solids
+ */
+#[allow(clippy::type_complexity)]
+pub fn apply_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Transform, &mut Knockback, Option<&BoxCollider>, Option<&CircleCollider>)>,
+    entities: Query<(Option<&BoxCollider>, Option<&CircleCollider>, &Transform), Without<Knockback>>,
+    mut solid_grid: ResMut<SolidGrid>,
+) {
+    for (entity, mut transform, mut knockback, box_collider, circle_collider) in query.iter_mut() {
+        knockback.time_left -= time.delta_seconds();
+        if knockback.time_left <= 0.0 {
+            commands.entity(entity).remove::<Knockback>();
+            continue;
+        }
+
+        let delta = knockback.velocity * (knockback.time_left / knockback.duration) * time.delta_seconds();
+        let delta = match as_collider(box_collider, circle_collider) {
+            Some(collider) => {
+                let (min, max) = collider.shape(&transform).bounds();
+                let near = solid_grid.near(min, max, delta);
+                let solids = blocking_solids(collider, near.filter_map(|other| entities.get(other).ok()));
+                sweep_aabb(min, max, delta, &solids)
+            }
+            None => delta,
+        };
+        transform.translation += delta.extend(0.0);
+    }
+}
@@ -1,11 +1,13 @@
 use std::{io::Write, time::Instant};
 
 use crate::components::LoadingText;
-use crate::resources::WORLD_SIZE;
+use crate::resources::{MyStates, TILE_SIZE, WORLD_SIZE};
 use crate::resources::assets::MyAssets;
 use crate::systems::print_pc_data_to_debug;
 use bevy::prelude::*;
-use bevy::utils::tracing::field::debug;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
 
 /*
 Resources
@@ -28,12 +30,66 @@ const MAX_LEAF_SIZE: u32 = 64;
 
 const MIN_ROOM_SIZE: u32 = MIN_LEAF_SIZE - 2;
 
+// chance for a room other than the first to be a treasure room
+const TREASURE_ROOM_CHANCE: f64 = 0.15;
+
+/*
+Tile indices into the base_tilemap texture (TXTilemap.png)
+The sheet is 40 tiles wide, so index = row * 40 + column
+ */
+pub const TILEMAP_COLUMNS: u32 = 40;
+
+// solid rock that never touches a floor, left empty so it renders as darkness
+pub const TILE_ROCK: u32 = 0;
+// plain stone slab
+pub const TILE_FLOOR: u32 = TILEMAP_COLUMNS + 28;
+// brick face of a wall seen from the front
+pub const TILE_WALL: u32 = 15 * TILEMAP_COLUMNS + 2;
+pub const TILE_WALL_LEFT_END: u32 = 15 * TILEMAP_COLUMNS + 1;
+pub const TILE_WALL_RIGHT_END: u32 = 15 * TILEMAP_COLUMNS + 3;
+// stone cap running along the top of a wall
+pub const TILE_WALL_TOP: u32 = 14 * TILEMAP_COLUMNS + 2;
+pub const TILE_WALL_TOP_LEFT: u32 = 14 * TILEMAP_COLUMNS + 1;
+pub const TILE_WALL_TOP_RIGHT: u32 = 14 * TILEMAP_COLUMNS + 3;
+// narrow brick column for walls only one tile thick
+pub const TILE_WALL_PILLAR: u32 = 18 * TILEMAP_COLUMNS + 1;
+// top left of the staircase, 2 tiles wide and 3 tall
+pub const TILE_STAIRS: u32 = TILEMAP_COLUMNS + 1;
+pub const STAIRS_SIZE: UVec2 = UVec2::new(2, 3);
+
 #[derive(Debug, Clone)]
 pub struct Room {
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Room {
+    pub fn center(&self) -> UVec2 {
+        UVec2::new(self.x + self.w / 2, self.y + self.h / 2)
+    }
+
+    /*
+    A random floor tile of the room, kept off the tiles next to the walls when the room is big enough
+     */
+    pub fn random_tile<R: Rng>(&self, rng: &mut R) -> UVec2 {
+        let (min_x, max_x) = if self.w > 2 { (self.x + 1, self.x + self.w - 1) } else { (self.x, self.x + self.w) };
+        let (min_y, max_y) = if self.h > 2 { (self.y + 1, self.y + self.h - 1) } else { (self.y, self.y + self.h) };
+        UVec2::new(rng.gen_range(min_x..max_x), rng.gen_range(min_y..max_y))
+    }
+}
+
+/*
+What a room is for, see Dungeon::room_kind
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub enum RoomKind {
+    // where the player starts
+    Start,
+    Normal,
+    // more and better loot lying around
+    Treasure,
 }
 
 #[derive(Debug, Clone)]
@@ -48,77 +104,174 @@ pub struct Leaf {
     pub halls: Vec<Room>,
 }
 
+/*
+A generated dungeon
+Until generate is run this only holds the size and seed the next dungeon will be built with
+ */
 pub struct Dungeon {
     pub tile_map: Vec<Vec<u32>>,
+    pub rooms: Vec<Room>,
+    pub halls: Vec<Room>,
+    // seed the BSP pipeline was (or will be) run with, so a layout can be reproduced
+    pub seed: u64,
+    // how deep the dungeon is, the first floor is 1 and enemies get stronger the deeper it goes
+    pub floor: u32,
     width: u32,
     height: u32,
 }
 
 impl Dungeon {
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
         Self {
             width: WORLD_SIZE.x,
             height: WORLD_SIZE.y,
             tile_map: Vec::new(),
+            rooms: Vec::new(),
+            halls: Vec::new(),
+            seed,
+            floor: 1,
         }
     }
 
+    /*
+    Runs the whole generation pipeline and returns the finished dungeon
+    This does not touch the ECS, so it can be called without an App or a window
+     */
+    pub fn generate(width: u32, height: u32, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let leafs = Self::gen_leafs(width, height, &mut rng);
+
+        let base_map = Self::format_map(Self::draw_map(&leafs, width, height));
+
+        let mut dungeon = Self {
+            width,
+            height,
+            tile_map: Vec::new(),
+            rooms: leafs.iter().filter_map(|leaf| leaf.room.clone()).collect(),
+            halls: leafs.iter().flat_map(|leaf| leaf.halls.iter().cloned()).collect(),
+            seed,
+            floor: 1,
+        };
+        dungeon.base_map_to_tile_map(base_map);
+        dungeon
+    }
+
+    /*
+    Go down to the next floor, it's built from a seed worked out from this one's,
+    so the same first seed always leads down through the same floors
+    Nothing is generated until the DungeonGeneration state runs again
+     */
+    pub fn descend(&mut self) {
+        self.floor += 1;
+        self.seed = StdRng::seed_from_u64(self.seed).gen();
+    }
+
+    #[allow(dead_code)]
     pub fn change_world_size(&mut self, new_size: UVec2) {
         self.width = new_size.x;
         self.height = new_size.y;
     }
 
-    pub fn gen_dungeon_base_map(width: u32, height: u32) -> Vec<Vec<char>> {
+    /*
+    Where the player starts, the center of the first room in world space
+    Falls back to the center of the map if nothing has been generated yet
+     */
+    pub fn spawn_point(&self) -> Vec2 {
+        let tile = self
+            .rooms
+            .first()
+            .map(|room| room.center())
+            .unwrap_or_else(|| UVec2::new(self.width / 2, self.height / 2));
+
+        Vec2::new(tile.x as f32 * TILE_SIZE.x, tile.y as f32 * TILE_SIZE.y)
+    }
+
+    /*
+    Where the stairs are, the center of the room farthest from the one the player starts in
+    Falls back to the spawn point if there is only one room
+     */
+    pub fn stairs_point(&self) -> Vec2 {
+        let start = match self.rooms.first() {
+            Some(room) => room.center().as_vec2(),
+            None => return self.spawn_point(),
+        };
+        let tile = self
+            .rooms
+            .iter()
+            .map(|room| room.center())
+            .max_by(|a, b| a.as_vec2().distance_squared(start).total_cmp(&b.as_vec2().distance_squared(start)))
+            .unwrap_or_else(|| start.as_uvec2());
+
+        Vec2::new(tile.x as f32 * TILE_SIZE.x, tile.y as f32 * TILE_SIZE.y)
+    }
+
+    // width and height in tiles
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.width, self.height)
+    }
+
+    /*
+    The first room is where the player starts, some of the others are treasure rooms
+    Which ones comes from the seed, so the same seed always has the same treasure rooms
+     */
+    pub fn room_kind(&self, index: usize) -> RoomKind {
+        if index == 0 {
+            return RoomKind::Start;
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(index as u64));
+        if rng.gen_bool(TREASURE_ROOM_CHANCE) {
+            RoomKind::Treasure
+        } else {
+            RoomKind::Normal
+        }
+    }
+
+    /*
+    Every random decision of the BSP pipeline is drawn from the rng passed in here,
+    so the same seed and size always produce the same layout
+     */
+    fn gen_leafs<R: Rng>(width: u32, height: u32, rng: &mut R) -> Vec<Leaf> {
+        let mut root = Leaf::new(0, 0, width, height);
+        Self::split_leaf(&mut root, rng);
+
+        // rooms and halls have to be made on the tree itself, halls connect the rooms of the
+        // children so they need to be the same rooms that end up drawn
+        root.create_rooms(rng);
+
         let mut leafs: Vec<Leaf> = Vec::new();
-    
-        let root = Leaf::new(0, 0, width, height);
-    
-        leafs.push(root.clone());
-    
-        let mut did_split = true;
-    
-        while did_split {
-            did_split = false;
-    
-            let mut new_leafs: Vec<Leaf> = Vec::new();
-    
-            for leaf in leafs.iter_mut() {
-                if leaf.left_child.is_none() && leaf.right_child.is_none() {
-                    if leaf.width > MAX_LEAF_SIZE
-                        || leaf.height > MAX_LEAF_SIZE
-                        || rand::random::<i32>() % 100 > 25
-                    {
-                        if leaf.split() {
-                            did_split = true;
-                            new_leafs.push(*leaf.left_child.as_ref().unwrap().clone());
-                            new_leafs.push(*leaf.right_child.as_ref().unwrap().clone());
-                        }
-                    }
-                }
+        root.flatten_into(&mut leafs);
+        leafs
+    }
+
+    /*
+    Keep splitting until the leafs are small enough, leafs that could still be split are
+    left alone 25% of the time so room sizes vary
+     */
+    fn split_leaf<R: Rng>(leaf: &mut Leaf, rng: &mut R) {
+        if (leaf.width > MAX_LEAF_SIZE
+            || leaf.height > MAX_LEAF_SIZE
+            || rng.gen_range(0..100) > 25)
+            && leaf.split(rng)
+        {
+            if let Some(left_child) = leaf.left_child.as_mut() {
+                Self::split_leaf(left_child, rng);
+            }
+            if let Some(right_child) = leaf.right_child.as_mut() {
+                Self::split_leaf(right_child, rng);
             }
-    
-            // add new leafs to leafs
-            leafs.append(&mut new_leafs);
-        }
-    
-        // create rooms
-        for leaf in leafs.iter_mut() {
-            leaf.create_rooms();
         }
-    
-        Self::draw_map(&leafs, root.width, root.height)
     }
 
-    fn draw_map(leafs: &Vec<Leaf>, width: u32, height: u32) -> Vec<Vec<char>> {
+    fn draw_map(leafs: &[Leaf], width: u32, height: u32) -> Vec<Vec<char>> {
         let mut grid = vec![vec!['#'; width as usize]; height as usize];
     
-        let mut i = 0;
         for leaf in leafs {
-            if leaf.room.is_some() {
-                if leaf.room.is_none() {
-                    continue;
-                }
-                let room = leaf.room.as_ref().unwrap();
+            if let Some(room) = &leaf.room {
                 // draw room with # as walls and . as floor
                 for x in room.x..(room.x + room.w) {
                     for y in room.y..room.y + room.h {
@@ -135,15 +288,14 @@ impl Dungeon {
                     }
                 }
             }
-    
-            i += 1;
         }
     
         grid
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn format_map(base_map: Vec<Vec<char>>) -> Vec<Vec<char>> {
-        let mut map = base_map.clone();
+        let mut map = base_map;
         for y in 0..map.len() {
             for x in 0..map[y].len() {
                 // change char of walls to depend on surrounding tiles (left, right, up, down)
@@ -297,19 +449,163 @@ impl Dungeon {
         map
     }
 
-    pub(crate) fn base_map_to_tile_map(&mut self, map: Vec<Vec<char>>) {
-        let mut tile_map = Vec::new();
-        for y in 0..map.len() {
-            let mut row = Vec::new();
-            for x in 0..map[y].len() {
-                let tile = match map[y][x] {
-                    '.' => 0,
-                    _ => 1, // all walls for now will be the same
-                };
-                row.push(tile);
+    /*
+    Map the wall classes produced by format_map onto tiles of the base tilemap
+    Anything that is not a floor is treated as a wall by the rest of the game
+     */
+    fn char_to_tile(tile_char: char) -> u32 {
+        match tile_char {
+            '.' => TILE_FLOOR,
+            // straight walls
+            '─' | '┬' => TILE_WALL,
+            '┴' => TILE_WALL_TOP,
+            '│' => TILE_WALL_PILLAR,
+            // corners
+            '┌' => TILE_WALL_TOP_LEFT,
+            '┐' => TILE_WALL_TOP_RIGHT,
+            '└' => TILE_WALL_LEFT_END,
+            '┘' => TILE_WALL_RIGHT_END,
+            // T-junctions
+            '├' => TILE_WALL_LEFT_END,
+            '┤' => TILE_WALL_RIGHT_END,
+            _ => TILE_ROCK,
+        }
+    }
+
+    /*
+    Tile index at a tile position, rows of the tile map are stacked along the world y axis
+    Returns None outside of the map
+     */
+    pub fn tile(&self, x: i32, y: i32) -> Option<u32> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        self.tile_map.get(y as usize)?.get(x as usize).copied()
+    }
+
+    /*
+    Anything that is not floor blocks movement, including everything outside of the map
+     */
+    pub fn is_wall(&self, x: i32, y: i32) -> bool {
+        self.tile(x, y) != Some(TILE_FLOOR)
+    }
+
+    /*
+    Tile a world position falls in, tiles are centered on multiples of TILE_SIZE
+     */
+    pub fn world_to_tile(position: Vec2) -> IVec2 {
+        (position / Vec2::new(TILE_SIZE.x, TILE_SIZE.y) + Vec2::splat(0.5)).floor().as_ivec2()
+    }
+
+    pub fn tile_to_world(tile: IVec2) -> Vec2 {
+        tile.as_vec2() * Vec2::new(TILE_SIZE.x, TILE_SIZE.y)
+    }
+
+    /*
+    Whether a straight line between two world positions stays clear of walls
+    Walks every tile the line passes through (Amanatides & Woo), so it can't slip through
+    the gap where two walls meet diagonally
+     */
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        let tile_size = Vec2::new(TILE_SIZE.x, TILE_SIZE.y);
+        // in tile units, shifted so tile x covers x..x+1
+        let start = from / tile_size + Vec2::splat(0.5);
+        let end = to / tile_size + Vec2::splat(0.5);
+        let delta = end - start;
+
+        let mut tile = start.floor().as_ivec2();
+        let end_tile = end.floor().as_ivec2();
+
+        let step = |d: f32| if d > 0.0 { 1 } else if d < 0.0 { -1 } else { 0 };
+        let step = IVec2::new(step(delta.x), step(delta.y));
+        // how far along the line (0 to 1) it takes to cross one whole tile on each axis
+        let t_delta = Vec2::new(
+            if delta.x != 0.0 { 1.0 / delta.x.abs() } else { f32::INFINITY },
+            if delta.y != 0.0 { 1.0 / delta.y.abs() } else { f32::INFINITY },
+        );
+        // how far along the line the next tile edge on each axis is
+        let first_edge = |start: f32, tile: i32, step: i32, t_delta: f32| match step {
+            1 => (tile as f32 + 1.0 - start) * t_delta,
+            -1 => (start - tile as f32) * t_delta,
+            _ => f32::INFINITY,
+        };
+        let mut t_max = Vec2::new(
+            first_edge(start.x, tile.x, step.x, t_delta.x),
+            first_edge(start.y, tile.y, step.y, t_delta.y),
+        );
+
+        let steps = (end_tile - tile).abs();
+        for _ in 0..=(steps.x + steps.y) {
+            if self.is_wall(tile.x, tile.y) {
+                return false;
+            }
+            if tile == end_tile {
+                return true;
+            }
+
+            if t_max.x < t_max.y {
+                tile.x += step.x;
+                t_max.x += t_delta.x;
+            } else {
+                tile.y += step.y;
+                t_max.y += t_delta.y;
             }
-            tile_map.push(row);
         }
+
+        !self.is_wall(end_tile.x, end_tile.y)
+    }
+
+    /*
+    Cover every wall tile with as few rectangles as possible
+    Runs of walls along a row are grown downwards while the whole run below is still wall,
+    so each rectangle can become a single collider instead of one per tile
+     */
+    pub fn wall_rects(&self) -> Vec<Room> {
+        let mut covered = vec![vec![false; self.width as usize]; self.height as usize];
+        let mut rects = Vec::new();
+
+        let free = |covered: &Vec<Vec<bool>>, x: u32, y: u32| {
+            self.is_wall(x as i32, y as i32) && !covered[y as usize][x as usize]
+        };
+
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                if !free(&covered, x, y) {
+                    x += 1;
+                    continue;
+                }
+
+                let mut w = 1;
+                while x + w < self.width && free(&covered, x + w, y) {
+                    w += 1;
+                }
+
+                let mut h = 1;
+                while y + h < self.height && (x..x + w).all(|rx| free(&covered, rx, y + h)) {
+                    h += 1;
+                }
+
+                for row in covered.iter_mut().skip(y as usize).take(h as usize) {
+                    for cell in row.iter_mut().skip(x as usize).take(w as usize) {
+                        *cell = true;
+                    }
+                }
+
+                rects.push(Room { x, y, w, h });
+                x += w;
+            }
+        }
+
+        rects
+    }
+
+    pub(crate) fn base_map_to_tile_map(&mut self, map: Vec<Vec<char>>) {
+        let tile_map = map
+            .iter()
+            .map(|map_row| map_row.iter().map(|tile_char| Self::char_to_tile(*tile_char)).collect())
+            .collect();
     
         self.tile_map = tile_map;
     }
@@ -329,8 +625,8 @@ impl Leaf {
         }
     }
 
-    pub fn split(&mut self) -> bool {
-        if !self.left_child.is_none() || !self.right_child.is_none() {
+    pub fn split<R: Rng>(&mut self, rng: &mut R) -> bool {
+        if self.left_child.is_some() || self.right_child.is_some() {
             return false;
         }
 
@@ -340,17 +636,16 @@ impl Leaf {
         if the height is > 25% larger than the width, we split horizontally
         otherwise we split randomly
         */
-        let split_horizontal: bool;
-        if self.width > self.height && (self.width / self.height) as f32 >= 1.25 {
+        let split_horizontal = if self.width > self.height && (self.width / self.height) as f32 >= 1.25 {
             // split vertically
-            split_horizontal = false;
+            false
         } else if self.height > self.width && (self.height / self.width) as f32 >= 1.25 {
             // split horizontally
-            split_horizontal = true;
+            true
         } else {
             // split randomly
-            split_horizontal = rand::random::<bool>();
-        }
+            rng.gen::<bool>()
+        };
 
         let max = if split_horizontal {
             self.height - MIN_LEAF_SIZE
@@ -362,7 +657,7 @@ impl Leaf {
             return false;
         }
 
-        let split = rand::random::<u32>() % (max - MIN_LEAF_SIZE) + MIN_LEAF_SIZE;
+        let split = rng.gen_range(MIN_LEAF_SIZE..max);
 
         if split_horizontal {
             self.left_child = Some(Box::new(Leaf::new(self.x, self.y, self.width, split)));
@@ -385,21 +680,21 @@ impl Leaf {
         true
     }
 
-    pub fn create_rooms(&mut self) {
+    pub fn create_rooms<R: Rng>(&mut self, rng: &mut R) {
         // there is already a room here
         if self.room.is_some() {
             return;
         }
 
-        if !self.left_child.is_none() || !self.right_child.is_none() {
-            if !self.left_child.is_none() {
-                self.left_child.as_mut().unwrap().create_rooms();
+        if self.left_child.is_some() || self.right_child.is_some() {
+            if let Some(left_child) = self.left_child.as_mut() {
+                left_child.create_rooms(rng);
             }
-            if !self.right_child.is_none() {
-                self.right_child.as_mut().unwrap().create_rooms();
+            if let Some(right_child) = self.right_child.as_mut() {
+                right_child.create_rooms(rng);
             }
-            if !self.left_child.is_none() && !self.right_child.is_none() {
-                self.create_halls();
+            if self.left_child.is_some() && self.right_child.is_some() {
+                self.create_halls(rng);
 
                 // make sure the halls don't exceed the bounds of the leaf
                 for hall in self.halls.iter_mut() {
@@ -420,11 +715,11 @@ impl Leaf {
                 }
             }
         } else {
-            let room_width = rand::random::<u32>() % (self.width - MIN_ROOM_SIZE) + MIN_ROOM_SIZE;
-            let room_height = rand::random::<u32>() % (self.height - MIN_ROOM_SIZE) + MIN_ROOM_SIZE;
+            let room_width = rng.gen_range(MIN_ROOM_SIZE..self.width);
+            let room_height = rng.gen_range(MIN_ROOM_SIZE..self.height);
 
-            let room_x = rand::random::<u32>() % (self.width - room_width);
-            let room_y = rand::random::<u32>() % (self.height - room_height);
+            let room_x = rng.gen_range(0..self.width - room_width);
+            let room_y = rng.gen_range(0..self.height - room_height);
 
             self.room = Some(Room {
                 x: self.x + room_x,
@@ -435,80 +730,89 @@ impl Leaf {
         }
     }
 
-    #[allow(dead_code)]
-    pub fn get_room(&self) -> Option<Room> {
-        if !self.room.is_none() {
-            return Some(self.room.clone().unwrap());
+    pub fn get_room<R: Rng>(&self, rng: &mut R) -> Option<Room> {
+        if self.room.is_some() {
+            return self.room.clone();
         }
 
-        let l_room: Option<Room>;
-        let r_room: Option<Room>;
-
-        if !self.left_child.is_none() {
-            l_room = self.left_child.as_ref().unwrap().get_room();
-        } else {
-            l_room = None;
+        let l_room = self.left_child.as_ref().and_then(|leaf| leaf.get_room(rng));
+        let r_room = self.right_child.as_ref().and_then(|leaf| leaf.get_room(rng));
+
+        match (l_room, r_room) {
+            (None, None) => None,
+            (None, Some(r_room)) => Some(r_room),
+            (Some(l_room), None) => Some(l_room),
+            (Some(l_room), Some(r_room)) => {
+                if rng.gen::<bool>() {
+                    Some(l_room)
+                } else {
+                    Some(r_room)
+                }
+            }
         }
+    }
 
-        if !self.right_child.is_none() {
-            r_room = self.right_child.as_ref().unwrap().get_room();
-        } else {
-            r_room = None;
-        }
+    /*
+    Move every leaf of the tree into leafs, parents before their children
+     */
+    fn flatten_into(mut self, leafs: &mut Vec<Leaf>) {
+        let left_child = self.left_child.take();
+        let right_child = self.right_child.take();
+        leafs.push(self);
 
-        if l_room.is_none() && r_room.is_none() {
-            return None;
-        } else if l_room.is_none() {
-            return Some(r_room.unwrap());
-        } else if r_room.is_none() {
-            return Some(l_room.unwrap());
-        } else {
-            if rand::random::<bool>() {
-                return Some(l_room.unwrap());
-            } else {
-                return Some(r_room.unwrap());
-            }
+        if let Some(left_child) = left_child {
+            left_child.flatten_into(leafs);
+        }
+        if let Some(right_child) = right_child {
+            right_child.flatten_into(leafs);
         }
     }
 
-    pub fn create_halls(&mut self) {
+    pub fn create_halls<R: Rng>(&mut self, rng: &mut R) {
         // TODO: Change back to old algorithm
 
         // connect rooms by adding halls which are rooms with a width of 1
-        let l_room = self.left_child.as_ref().unwrap().get_room().unwrap();
-        let r_room = self.right_child.as_ref().unwrap().get_room().unwrap();
+        let l_room = self.left_child.as_ref().unwrap().get_room(rng).unwrap();
+        let r_room = self.right_child.as_ref().unwrap().get_room(rng).unwrap();
 
         let l_center_x = l_room.x + l_room.w / 2;
         let l_center_y = l_room.y + l_room.h / 2;
         let r_center_x = r_room.x + r_room.w / 2;
         let r_center_y = r_room.y + r_room.h / 2;
 
-        if rand::random::<bool>() {
+        // the halls run from the smaller coordinate to the larger one, whichever room that is,
+        // and include both ends so the two legs meet at the corner
+        let min_x = l_center_x.min(r_center_x);
+        let min_y = l_center_y.min(r_center_y);
+        let w = r_center_x.abs_diff(l_center_x) + 1;
+        let h = r_center_y.abs_diff(l_center_y) + 1;
+
+        if rng.gen::<bool>() {
             // first move horizontally, then vertically
             self.halls.push(Room {
-                x: l_center_x,
+                x: min_x,
                 y: l_center_y,
-                w: (r_center_x as i32 - l_center_x as i32).abs() as u32,
+                w,
                 h: 1,
             });
             self.halls.push(Room {
                 x: r_center_x,
-                y: l_center_y,
+                y: min_y,
                 w: 1,
-                h: (r_center_y as i32 - l_center_y as i32).abs() as u32,
+                h,
             });
         } else {
             // first move vertically, then horizontally
             self.halls.push(Room {
                 x: l_center_x,
-                y: l_center_y,
+                y: min_y,
                 w: 1,
-                h: (r_center_y as i32 - l_center_y as i32).abs() as u32,
+                h,
             });
             self.halls.push(Room {
-                x: l_center_x,
+                x: min_x,
                 y: r_center_y,
-                w: (r_center_x as i32 - l_center_x as i32).abs() as u32,
+                w,
                 h: 1,
             });
         }
@@ -520,20 +824,18 @@ fn gen_dungeon_stress_test_internal(width: u32, height: u32) {
     //let max_room_size = UVec2::new(16, 16);
     /*let mut file = std::fs::File::create(format!("generation.txt")).unwrap();*/
 
-    let mut i = 0;
     let max = 10;
 
     let mut run_times: Vec<u128> = Vec::new();
 
-    while i < max {
+    // seed each run with its index so timings are comparable between runs of the test
+    for seed in 0..max {
         let start_time = Instant::now();
 
-        Dungeon::gen_dungeon_base_map(width, height);
+        Dungeon::generate(width, height, seed);
 
         let run_time = start_time.elapsed().as_millis();
-        run_times.push(run_time.clone());
-
-        i += 1;
+        run_times.push(run_time);
     }
 
     run_times.sort();
@@ -555,7 +857,7 @@ fn gen_dungeon_stress_test_internal(width: u32, height: u32) {
 }
 
 #[allow(dead_code)]
-pub fn gen_dungeon_stress_test(mut commands: Commands, mut assets: ResMut<MyAssets>) {
+pub fn gen_dungeon_stress_test() {
     print_pc_data_to_debug();
     debug!("Starting stress test");
 
@@ -572,20 +874,23 @@ pub fn gen_dungeon_stress_test(mut commands: Commands, mut assets: ResMut<MyAsse
     std::process::exit(0);
 }
 
-fn print_leaf_data(leafs: &Vec<Leaf>) {
-    let mut file = std::fs::File::create(format!("leaf_data.txt")).unwrap();
-    let mut i = 0;
-    for leaf in leafs {
+#[allow(dead_code)]
+fn print_leaf_data(leafs: &[Leaf]) {
+    let mut file = std::fs::File::create("leaf_data.txt").unwrap();
+    for (i, leaf) in leafs.iter().enumerate() {
         write!(file, "Leaf {}\r", i).unwrap();
         write!(file, "{:?}\r", leaf).unwrap();
-        i += 1;
     }
 }
 
-//pub fn gen_dungeon(width: u32, height: u32) {
+/*
+Shows the loading text, builds the dungeon from the size, seed and floor already on the resource,
+then hands over to the game
+ */
 pub fn gen_dungeon_system(
     mut commands: Commands,
     mut dungeon: ResMut<Dungeon>,
+    mut app_state: ResMut<State<MyStates>>,
     assets: Res<MyAssets>,
 ) {
     commands
@@ -615,23 +920,73 @@ pub fn gen_dungeon_system(
         )
         .insert(LoadingText);
 
-    let mut base_map = Dungeon::gen_dungeon_base_map(dungeon.width, dungeon.height);
-    Dungeon::format_map(base_map.clone()).clone_into(&mut base_map);
-    dungeon.base_map_to_tile_map(base_map.clone());
+    info!("Generating floor {}, {}x{} with seed {}", dungeon.floor, dungeon.width, dungeon.height, dungeon.seed);
+    let floor = dungeon.floor;
+    *dungeon = Dungeon::generate(dungeon.width, dungeon.height, dungeon.seed);
+    dungeon.floor = floor;
+    debug!("Dungeon generated with {} rooms and {} halls", dungeon.rooms.len(), dungeon.halls.len());
+
+    app_state.overwrite_set(MyStates::Game).unwrap_or_else(|e| error!("Failed to overwrite state: {:?}", e));
+}
 
-    // deallocate leafs
-    //leafs.clear();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rects(rooms: &[Room]) -> Vec<(u32, u32, u32, u32)> {
+        rooms.iter().map(|room| (room.x, room.y, room.w, room.h)).collect()
+    }
 
-    let mut file = std::fs::File::create(format!("generation.txt")).unwrap();
+    #[test]
+    fn same_seed_same_dungeon() {
+        for seed in [0, 1, 42, u64::MAX] {
+            let first = Dungeon::generate(128, 128, seed);
+            let second = Dungeon::generate(128, 128, seed);
 
-    // draw to file
-    for y in 0..dungeon.height {
-        for x in 0..dungeon.width {
-            write!(file, "{}", dungeon.tile_map[y as usize][x as usize]).unwrap();
+            assert_eq!(first.tile_map, second.tile_map, "tile map differs with seed {}", seed);
+            assert_eq!(rects(&first.rooms), rects(&second.rooms), "rooms differ with seed {}", seed);
+            assert_eq!(rects(&first.halls), rects(&second.halls), "halls differ with seed {}", seed);
         }
-        write!(file, "\r").unwrap();
     }
 
-    // exit the program
-    std::process::exit(0);
+    #[test]
+    fn generate_fills_the_whole_map() {
+        for (width, height) in [(128, 128), (96, 160)] {
+            let dungeon = Dungeon::generate(width, height, 7);
+
+            assert_eq!(dungeon.tile_map.len(), height as usize);
+            assert!(dungeon.tile_map.iter().all(|row| row.len() == width as usize));
+            assert_eq!(dungeon.size(), UVec2::new(width, height));
+            assert!(!dungeon.rooms.is_empty());
+            assert!(dungeon.rooms.iter().all(|room| room.w > 0 && room.h > 0));
+
+            let spawn = Dungeon::world_to_tile(dungeon.spawn_point());
+            assert_eq!(dungeon.tile(spawn.x, spawn.y), Some(TILE_FLOOR), "spawn point {} is not on the floor", spawn);
+        }
+    }
+
+    #[test]
+    fn descend_goes_down_the_same_floors() {
+        let mut first = Dungeon::with_seed(5);
+        let mut second = Dungeon::with_seed(5);
+
+        first.descend();
+        second.descend();
+        assert_eq!(first.floor, 2);
+        assert_ne!(first.seed, 5);
+        assert_eq!(first.seed, second.seed);
+
+        first.descend();
+        assert_eq!(first.floor, 3);
+        assert_ne!(first.seed, second.seed);
+    }
+
+    #[test]
+    fn different_seed_different_dungeon() {
+        let first = Dungeon::generate(128, 128, 1);
+        let second = Dungeon::generate(128, 128, 2);
+
+        assert_ne!(first.tile_map, second.tile_map);
+        assert_ne!(rects(&first.rooms), rects(&second.rooms));
+    }
 }
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::components::enemy::{EnemyBundle, EnemyCollider, EnemyKind};
+use crate::components::player::PlayerLevel;
+use crate::components::FloorEntity;
+use crate::resources::enemy_stats::{EnemyStats, EnemyStatsTable};
+use crate::resources::TILE_SIZE;
+use crate::systems::dungeon_gen::Dungeon;
+use crate::MyAssets;
+
+// the monster art is drawn at 150x150, this brings it closer to the 32x32 player
+const ENEMY_SCALE: f32 = 0.6;
+// floor tiles per enemy in a room
+const ROOM_AREA_PER_ENEMY: u32 = 40;
+const MAX_ENEMIES_PER_ROOM: u32 = 4;
+
+/*
+Put enemies in every room but the first one, where the player starts
+Bigger rooms get more enemies, what spawns and where comes from the dungeon seed so
+the same seed always has the same monsters
+How strong they are depends on the floor and the player's level, some of them are
+rolled as elites or champions
+ */
+pub fn spawn_enemies(
+    mut commands: Commands,
+    dungeon: Res<Dungeon>,
+    assets: Res<MyAssets>,
+    tables: Res<Assets<EnemyStatsTable>>,
+    player: Query<&PlayerLevel>,
+) {
+    let table = match tables.get(&assets.enemy_stats) {
+        Some(table) => table,
+        None => {
+            warn!("Enemy stats are not loaded, no enemies spawned");
+            return;
+        }
+    };
+    // the player is spawned in the same frame on the first floor, it's level 1 then
+    let level = table.level(dungeon.floor, player.get_single().map_or(1, |level| level.0));
+
+    let mut rng = StdRng::seed_from_u64(dungeon.seed);
+    let mut count = 0;
+
+    for room in dungeon.rooms.iter().skip(1) {
+        let enemies = (room.w * room.h / ROOM_AREA_PER_ENEMY).clamp(1, MAX_ENEMIES_PER_ROOM);
+
+        for _ in 0..enemies {
+            let kind = EnemyKind::ALL[rng.gen_range(0..EnemyKind::ALL.len())];
+            let tile = room.random_tile(&mut rng);
+            let rank = table.rank(rng.gen());
+
+            let stats = match table.stats(kind, level, rank) {
+                Some(stats) => stats,
+                None => {
+                    warn!("No stats for {:?}", kind);
+                    continue;
+                }
+            };
+            spawn_enemy(&mut commands, &assets, kind, &stats, Vec2::new(tile.x as f32 * TILE_SIZE.x, tile.y as f32 * TILE_SIZE.y));
+            count += 1;
+        }
+    }
+
+    info!("Spawned {} level {} enemies on floor {}", count, level, dungeon.floor);
+}
+
+pub fn spawn_enemy(commands: &mut Commands, assets: &MyAssets, kind: EnemyKind, stats: &EnemyStats, position: Vec2) -> Entity {
+    let atlas = assets
+        .atlas(&format!("{}/{}", kind.animation_set(), kind.idle_clip()))
+        .cloned()
+        .unwrap_or_default();
+    let transform = Transform::from_translation(position.extend(1.0)).with_scale(Vec3::splat(ENEMY_SCALE));
+
+    let mut enemy = commands.spawn_bundle(EnemyBundle::new(kind, stats, atlas, transform));
+    enemy.insert(FloorEntity);
+    match kind.collider(stats.scale) {
+        EnemyCollider::Box(collider) => enemy.insert(collider),
+        EnemyCollider::Circle(collider) => enemy.insert(collider),
+    };
+    if let Some(ranged_attack) = kind.ranged_attack() {
+        enemy.insert(ranged_attack);
+    }
+    if let Some(shield) = kind.shield() {
+        enemy.insert(shield);
+    }
+
+    enemy.id()
+}
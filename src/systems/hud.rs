@@ -0,0 +1,251 @@
+use bevy::prelude::*;
+
+use crate::components::hud::{ArmorText, FloorText, HealthFill, HealthText, Hud, LevelText, StatusStrip, XpFill, XpText};
+use crate::components::player::{PlayerAnimationState, PlayerLevel, PlayerXp};
+use crate::components::status::StatusEffects;
+use crate::components::{GameEntity, Health};
+use crate::resources::leveling::LevelCurve;
+use crate::systems::dungeon_gen::Dungeon;
+use crate::MyAssets;
+
+const HUD_FONT_SIZE: f32 = 26.0;
+const HUD_TITLE_FONT_SIZE: f32 = 18.0;
+const BAR_WIDTH: f32 = 200.0;
+const BAR_HEIGHT: f32 = 14.0;
+const BAR_BACKGROUND: Color = Color::rgba(0.05, 0.05, 0.08, 0.85);
+const HEALTH_COLOR: Color = Color::rgb(0.8, 0.15, 0.15);
+const XP_COLOR: Color = Color::rgb(0.3, 0.5, 0.95);
+const HARMFUL_COLOR: Color = Color::rgb(0.9, 0.4, 0.4);
+const HELPFUL_COLOR: Color = Color::rgb(0.5, 0.9, 0.5);
+
+fn hud_text(font: &Handle<Font>, font_size: f32, value: &str, color: Color) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font: font.clone(),
+            font_size,
+            color,
+        },
+    )
+    .with_style(Style {
+        margin: UiRect {
+            right: Val::Px(12.0),
+            ..default()
+        },
+        ..default()
+    })
+}
+
+fn hud_row() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            margin: UiRect {
+                bottom: Val::Px(4.0),
+                ..default()
+            },
+            ..default()
+        },
+        color: Color::NONE.into(),
+        ..default()
+    }
+}
+
+/*
+Background of a bar with the fill inside it, fill gets the marker that sets its width
+ */
+fn spawn_bar(parent: &mut ChildBuilder, color: Color, fill: impl Component) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(BAR_WIDTH), Val::Px(BAR_HEIGHT)),
+                margin: UiRect {
+                    right: Val::Px(8.0),
+                    ..default()
+                },
+                padding: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            color: BAR_BACKGROUND.into(),
+            ..default()
+        })
+        .with_children(|bar| {
+            bar.spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    ..default()
+                },
+                color: color.into(),
+                ..default()
+            })
+            .insert(fill);
+        });
+}
+
+/*
+Health and xp bars, level, floor and armor, and the effects the player is under, in the top left
+The update systems below fill it in
+It lasts the whole game, the floors below keep the one that's there
+ */
+pub fn spawn_hud(mut commands: Commands, assets: Res<MyAssets>, huds: Query<(), With<Hud>>) {
+    if !huds.is_empty() {
+        return;
+    }
+    let font = &assets.hud_font;
+    let title_font = &assets.hud_title_font;
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(15.0),
+                    left: Val::Px(15.0),
+                    ..default()
+                },
+                // top to bottom
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::FlexStart,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(Hud)
+        .insert(GameEntity)
+        .with_children(|hud| {
+            hud.spawn_bundle(hud_row()).with_children(|row| {
+                spawn_bar(row, HEALTH_COLOR, HealthFill);
+                row.spawn_bundle(hud_text(font, HUD_FONT_SIZE, "", Color::WHITE)).insert(HealthText);
+            });
+            hud.spawn_bundle(hud_row()).with_children(|row| {
+                spawn_bar(row, XP_COLOR, XpFill);
+                row.spawn_bundle(hud_text(font, HUD_FONT_SIZE, "", Color::WHITE)).insert(XpText);
+            });
+            hud.spawn_bundle(hud_row()).with_children(|row| {
+                row.spawn_bundle(hud_text(title_font, HUD_TITLE_FONT_SIZE, "", Color::WHITE)).insert(LevelText);
+                row.spawn_bundle(hud_text(title_font, HUD_TITLE_FONT_SIZE, "", Color::WHITE)).insert(FloorText);
+                row.spawn_bundle(hud_text(font, HUD_FONT_SIZE, "", Color::WHITE)).insert(ArmorText);
+            });
+            hud.spawn_bundle(hud_row()).insert(StatusStrip);
+        });
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_health_hud(
+    player: Query<(&Health, ChangeTrackers<Health>), With<PlayerAnimationState>>,
+    new_hud: Query<(), Added<Hud>>,
+    mut fill: Query<&mut Style, With<HealthFill>>,
+    mut texts: ParamSet<(Query<&mut Text, With<HealthText>>, Query<&mut Text, With<ArmorText>>)>,
+) {
+    let (health, tracker) = match player.get_single() {
+        Ok(player) => player,
+        Err(_) => return,
+    };
+    if !tracker.is_changed() && new_hud.is_empty() {
+        return;
+    }
+
+    for mut style in fill.iter_mut() {
+        style.size.width = Val::Percent(health.fraction().clamp(0.0, 1.0) * 100.0);
+    }
+    for mut text in texts.p0().iter_mut() {
+        text.sections[0].value = format!("{:.0}/{:.0}", health.hp.ceil(), health.max_hp.ceil());
+    }
+    for mut text in texts.p1().iter_mut() {
+        text.sections[0].value = format!("Armor {:.1}", health.armor);
+    }
+}
+
+/*
+The xp bar fills up from the xp the current level took to the xp the next one takes,
+and stays full at the highest level
+ */
+#[allow(clippy::type_complexity)]
+pub fn update_xp_hud(
+    assets: Res<MyAssets>,
+    curves: Res<Assets<LevelCurve>>,
+    player: Query<(&PlayerXp, &PlayerLevel, ChangeTrackers<PlayerXp>, ChangeTrackers<PlayerLevel>), With<PlayerAnimationState>>,
+    new_hud: Query<(), Added<Hud>>,
+    mut fill: Query<&mut Style, With<XpFill>>,
+    mut texts: ParamSet<(Query<&mut Text, With<XpText>>, Query<&mut Text, With<LevelText>>)>,
+) {
+    let (curve, (xp, level, xp_tracker, level_tracker)) = match (curves.get(&assets.levels), player.get_single()) {
+        (Some(curve), Ok(player)) => (curve, player),
+        _ => return,
+    };
+    if !xp_tracker.is_changed() && !level_tracker.is_changed() && new_hud.is_empty() {
+        return;
+    }
+
+    let previous = level.0.checked_sub(1).and_then(|level| curve.xp_for_next(level)).unwrap_or(0);
+    let (fraction, label) = match curve.xp_for_next(level.0) {
+        Some(next) if next > previous => (
+            xp.0.saturating_sub(previous) as f32 / (next - previous) as f32,
+            format!("{}/{} xp", xp.0, next),
+        ),
+        _ => (1.0, format!("{} xp", xp.0)),
+    };
+
+    for mut style in fill.iter_mut() {
+        style.size.width = Val::Percent(fraction.clamp(0.0, 1.0) * 100.0);
+    }
+    for mut text in texts.p0().iter_mut() {
+        text.sections[0].value = label.clone();
+    }
+    for mut text in texts.p1().iter_mut() {
+        text.sections[0].value = format!("Level {}", level.0);
+    }
+}
+
+pub fn update_floor_hud(
+    dungeon: Res<Dungeon>,
+    new_hud: Query<(), Added<Hud>>,
+    mut texts: Query<&mut Text, With<FloorText>>,
+) {
+    if !dungeon.is_changed() && new_hud.is_empty() {
+        return;
+    }
+
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("Floor {}", dungeon.floor);
+    }
+}
+
+/*
+One line for every effect with the whole seconds it has left
+The effects tick every frame, so the strip is only rebuilt when what it shows would be different
+ */
+pub fn update_status_hud(
+    mut commands: Commands,
+    assets: Res<MyAssets>,
+    player: Query<&StatusEffects, With<PlayerAnimationState>>,
+    new_hud: Query<(), Added<Hud>>,
+    strips: Query<Entity, With<StatusStrip>>,
+    mut shown: Local<Vec<(&'static str, bool, u32)>>,
+) {
+    let effects = match player.get_single() {
+        Ok(effects) => effects,
+        Err(_) => return,
+    };
+    let lines: Vec<_> = effects
+        .0
+        .iter()
+        .map(|active| (active.effect.kind.name(), active.effect.kind.is_harmful(), active.seconds_left().ceil() as u32))
+        .collect();
+    if *shown == lines && new_hud.is_empty() {
+        return;
+    }
+
+    for strip in strips.iter() {
+        commands.entity(strip).despawn_descendants();
+        commands.entity(strip).with_children(|strip| {
+            for (name, harmful, seconds) in lines.iter() {
+                let color = if *harmful { HARMFUL_COLOR } else { HELPFUL_COLOR };
+                strip.spawn_bundle(hud_text(&assets.hud_font, HUD_FONT_SIZE, &format!("{} {}s", name, seconds), color));
+            }
+        });
+    }
+    *shown = lines;
+}
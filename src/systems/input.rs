@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use crate::resources::input::{ActionInput, InputAction, InputBindings, INPUT_CONFIG_PATH};
+
+/*
+Turn the keyboard and gamepad state into actions
+Every connected gamepad can drive the player, the left stick is added to the Move actions
+ */
+pub fn update_action_input(
+    bindings: Res<InputBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut actions: ResMut<ActionInput>,
+) {
+    let ActionInput { pressed, just_pressed, movement } = &mut *actions;
+    pressed.clear();
+    just_pressed.clear();
+
+    for (action, keys) in bindings.keys.iter() {
+        if keyboard_input.any_pressed(keys.iter().copied()) {
+            pressed.insert(*action);
+        }
+        if keyboard_input.any_just_pressed(keys.iter().copied()) {
+            just_pressed.insert(*action);
+        }
+    }
+
+    let mut stick = Vec2::ZERO;
+    for gamepad in gamepads.iter() {
+        for (action, buttons) in bindings.gamepad_buttons.iter() {
+            let buttons = buttons.iter().map(|button| GamepadButton::new(*gamepad, *button));
+            if gamepad_buttons.any_pressed(buttons.clone()) {
+                pressed.insert(*action);
+            }
+            if gamepad_buttons.any_just_pressed(buttons) {
+                just_pressed.insert(*action);
+            }
+        }
+
+        let x = gamepad_axes.get(GamepadAxis::new(*gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+        let y = gamepad_axes.get(GamepadAxis::new(*gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.0);
+        let axis = Vec2::new(x, y);
+        if axis.length() > bindings.stick_dead_zone {
+            stick += axis;
+        }
+    }
+
+    let mut direction = Vec2::ZERO;
+    if pressed.contains(&InputAction::MoveLeft) {
+        direction.x -= 1.0;
+    }
+    if pressed.contains(&InputAction::MoveRight) {
+        direction.x += 1.0;
+    }
+    if pressed.contains(&InputAction::MoveUp) {
+        direction.y += 1.0;
+    }
+    if pressed.contains(&InputAction::MoveDown) {
+        direction.y -= 1.0;
+    }
+
+    // pressing a direction always means full speed, only the stick on its own moves slower
+    *movement = if direction != Vec2::ZERO {
+        direction.normalize()
+    } else {
+        stick.clamp_length_max(1.0)
+    };
+}
+
+/*
+Write the bindings back to the config file whenever they are rebound
+ */
+pub fn save_input_bindings(bindings: Res<InputBindings>) {
+    if bindings.is_changed() && !bindings.is_added() {
+        match bindings.save(INPUT_CONFIG_PATH) {
+            Ok(()) => info!("Input bindings saved to {}", INPUT_CONFIG_PATH),
+            Err(e) => warn!("Could not save input bindings to {}: {}", INPUT_CONFIG_PATH, e),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+
+use crate::components::combat::MeleeAttack;
+use crate::components::inventory::{EquipmentBonus, Inventory, InventoryPanel};
+use crate::components::stats::Stats;
+use crate::components::{GameEntity, Health};
+use crate::events::{ItemUsed, StatusEffectEvent};
+use crate::resources::input::{ActionInput, InputAction};
+use crate::resources::inventory::InventoryUi;
+use crate::resources::items::{EquipSlot, ItemDatabase, ItemUse};
+use crate::systems::dungeon_gen::Dungeon;
+use crate::MyAssets;
+
+const PANEL_FONT_SIZE: f32 = 28.0;
+const SELECTED_COLOR: Color = Color::YELLOW;
+const EMPTY_COLOR: Color = Color::GRAY;
+
+/*
+Swap the old equipment modifiers for the new ones whenever the inventory changes
+Vitality and armor go into Health, the max hp that comes or goes is given or taken from the
+current hp as well, and the flat damage goes onto the melee attack
+The dead are left as they are, or taking off armor would bring them back
+ */
+pub fn apply_equipment(
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    mut query: Query<(&Inventory, &mut EquipmentBonus, &mut Stats, &mut Health, &mut MeleeAttack), Changed<Inventory>>,
+) {
+    let items = match items.get(&assets.item_database) {
+        Some(items) => items,
+        None => return,
+    };
+
+    for (inventory, mut bonus, mut stats, mut health, mut attack) in query.iter_mut() {
+        if health.is_dead() {
+            continue;
+        }
+
+        let old = bonus.0;
+        let new = inventory.modifiers(items);
+
+        let max_hp = new.stats().max_hp_bonus() - old.stats().max_hp_bonus();
+        health.max_hp += max_hp;
+        health.hp = (health.hp + max_hp).min(health.max_hp).max(0.0);
+        health.armor += new.armor - old.armor + new.stats().armor_bonus() - old.stats().armor_bonus();
+        attack.damage += new.damage - old.damage;
+
+        *stats -= old.stats();
+        *stats += new.stats();
+        bonus.0 = new;
+    }
+}
+
+pub fn toggle_inventory(actions: Res<ActionInput>, mut ui: ResMut<InventoryUi>) {
+    if actions.just_pressed(InputAction::Inventory) {
+        ui.open = !ui.open;
+    }
+}
+
+/*
+While the panel is open the move actions pick a row and interact wears or uses the selected item,
+or takes it off when an equipment slot is selected
+ */
+pub fn navigate_inventory(
+    actions: Res<ActionInput>,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    mut ui: ResMut<InventoryUi>,
+    mut query: Query<(Entity, &mut Inventory, Option<&Health>)>,
+    mut used: EventWriter<ItemUsed>,
+) {
+    if !ui.open {
+        return;
+    }
+    let (items, (entity, mut inventory, health)) = match (items.get(&assets.item_database), query.get_single_mut()) {
+        (Some(items), Ok(inventory)) => (items, inventory),
+        _ => return,
+    };
+
+    let rows = inventory.stacks.len() + EquipSlot::ALL.len();
+    if actions.just_pressed(InputAction::MoveUp) && ui.selected > 0 {
+        ui.selected -= 1;
+    }
+    if actions.just_pressed(InputAction::MoveDown) && ui.selected + 1 < rows {
+        ui.selected += 1;
+    }
+
+    if actions.just_pressed(InputAction::Interact) {
+        let selected = ui.selected;
+        let stacks = inventory.stacks.len();
+        let usable = inventory
+            .stacks
+            .get(selected)
+            .and_then(|stack| items.get(&stack.item))
+            .is_some_and(|item| item.on_use.is_some());
+        if usable && health.is_some_and(Health::is_dead) {
+            info!("The dead can't use items");
+        } else if usable {
+            if let Some(item) = inventory.take(selected) {
+                used.send(ItemUsed { entity, item });
+            }
+        } else if selected < stacks {
+            inventory.equip(selected, items);
+        } else if !inventory.unequip(EquipSlot::ALL[selected - stacks], items) {
+            info!("No room in the inventory");
+        }
+    }
+
+    // stacks come and go, keep the selection on a row that's there
+    let rows = inventory.stacks.len() + EquipSlot::ALL.len();
+    if ui.selected >= rows {
+        ui.selected = rows - 1;
+    }
+}
+
+/*
+Do what a used item does for whoever used it
+Nothing brings back the dead, an item used by them is just gone
+ */
+pub fn use_items(
+    mut used: EventReader<ItemUsed>,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    dungeon: Res<Dungeon>,
+    mut query: Query<(&mut Health, &mut Transform)>,
+    mut effects: EventWriter<StatusEffectEvent>,
+) {
+    let items = match items.get(&assets.item_database) {
+        Some(items) => items,
+        None => return,
+    };
+
+    for event in used.iter() {
+        let (item, (mut health, mut transform)) = match (items.get(&event.item), query.get_mut(event.entity)) {
+            (Some(item), Ok(user)) => (item, user),
+            _ => continue,
+        };
+        if health.is_dead() {
+            continue;
+        }
+
+        match item.on_use {
+            Some(ItemUse::Heal(amount)) => health.hp = (health.hp + amount).min(health.max_hp),
+            Some(ItemUse::Effect(effect)) => effects.send(StatusEffectEvent {
+                target: event.entity,
+                source: event.entity,
+                effect,
+            }),
+            Some(ItemUse::TeleportToStairs) => {
+                transform.translation = dungeon.stairs_point().extend(transform.translation.z);
+            }
+            None => {}
+        }
+        info!("Used {}", item.name);
+    }
+}
+
+/*
+Rebuild the inventory panel whenever it is opened, closed or what it shows changes
+ */
+pub fn draw_inventory_ui(
+    mut commands: Commands,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    ui: Res<InventoryUi>,
+    inventory: Query<(&Inventory, ChangeTrackers<Inventory>)>,
+    panels: Query<Entity, With<InventoryPanel>>,
+) {
+    let inventory_changed = inventory.iter().any(|(_, tracker)| tracker.is_changed());
+    if !ui.is_changed() && !inventory_changed {
+        return;
+    }
+
+    for panel in panels.iter() {
+        commands.entity(panel).despawn_recursive();
+    }
+    if !ui.open {
+        return;
+    }
+    let (items, inventory) = match (items.get(&assets.item_database), inventory.get_single()) {
+        (Some(items), Ok((inventory, _))) => (items, inventory),
+        _ => return,
+    };
+
+    let line = |text: String, color: Color| {
+        TextBundle::from_section(
+            text,
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: PANEL_FONT_SIZE,
+                color,
+            },
+        )
+    };
+    let marker = |row: usize| if row == ui.selected { "> " } else { "  " };
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(15.0),
+                    right: Val::Px(15.0),
+                    ..default()
+                },
+                // top to bottom
+                flex_direction: FlexDirection::ColumnReverse,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            color: Color::rgba(0.05, 0.05, 0.08, 0.85).into(),
+            ..default()
+        })
+        .insert(InventoryPanel)
+        .insert(GameEntity)
+        .with_children(|panel| {
+            panel.spawn_bundle(line(
+                format!("Inventory {}/{}", inventory.stacks.len(), inventory.capacity),
+                Color::WHITE,
+            ));
+
+            if inventory.stacks.is_empty() {
+                panel.spawn_bundle(line("  Nothing".to_string(), EMPTY_COLOR));
+            }
+            for (row, stack) in inventory.stacks.iter().enumerate() {
+                let (name, color) = match items.get(&stack.item) {
+                    Some(item) => (item.name.as_str(), item.rarity.color()),
+                    None => (stack.item.as_str(), EMPTY_COLOR),
+                };
+                let count = if stack.count > 1 { format!(" x{}", stack.count) } else { String::new() };
+                let color = if row == ui.selected { SELECTED_COLOR } else { color };
+                panel.spawn_bundle(line(format!("{}{}{}", marker(row), name, count), color));
+            }
+
+            panel.spawn_bundle(line("Equipped".to_string(), Color::WHITE));
+            for (index, slot) in EquipSlot::ALL.iter().enumerate() {
+                let row = inventory.stacks.len() + index;
+                let (name, color) = match inventory.equipped.get(slot).and_then(|id| items.get(id)) {
+                    Some(item) => (item.name.as_str(), item.rarity.color()),
+                    None => ("-", EMPTY_COLOR),
+                };
+                let color = if row == ui.selected { SELECTED_COLOR } else { color };
+                panel.spawn_bundle(line(format!("{}{}: {}", marker(row), slot.name(), name), color));
+            }
+        });
+}
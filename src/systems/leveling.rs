@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::components::enemy::EnemyLevel;
+use crate::components::player::{PlayerLevel, PlayerXp};
+use crate::components::stats::Stats;
+use crate::components::Health;
+use crate::events::{DeathEvent, LevelUpEvent};
+use crate::resources::leveling::LevelCurve;
+use crate::MyAssets;
+
+/*
+Whoever landed the killing blow on an enemy gets its xp, if they collect xp at all
+ */
+pub fn award_xp(
+    mut deaths: EventReader<DeathEvent>,
+    enemies: Query<(&EnemyLevel, &crate::components::person::Name)>,
+    mut players: Query<&mut PlayerXp>,
+) {
+    for death in deaths.iter() {
+        let (level, name) = match enemies.get(death.entity) {
+            Ok(enemy) => enemy,
+            Err(_) => continue,
+        };
+        if let Ok(mut xp) = players.get_mut(death.killer) {
+            xp.0 += level.xp;
+            debug!("{} killed, {} xp", name.0, xp.0);
+        }
+    }
+}
+
+/*
+Go up a level for every threshold of the level curve the player's xp has passed
+Each level adds the curve's stat points, the new hp is given straight away
+ */
+pub fn level_up(
+    assets: Res<MyAssets>,
+    curves: Res<Assets<LevelCurve>>,
+    mut query: Query<(Entity, &PlayerXp, &mut PlayerLevel, &mut Stats, &mut Health), Changed<PlayerXp>>,
+    mut level_ups: EventWriter<LevelUpEvent>,
+) {
+    let curve = match curves.get(&assets.levels) {
+        Some(curve) => curve,
+        None => return,
+    };
+
+    for (entity, xp, mut level, mut stats, mut health) in query.iter_mut() {
+        while level.0 < curve.level_for(xp.0) {
+            level.0 += 1;
+            *stats += curve.gains;
+            health.max_hp += curve.gains.max_hp_bonus();
+            health.hp += curve.gains.max_hp_bonus();
+            health.armor += curve.gains.armor_bonus();
+
+            level_ups.send(LevelUpEvent {
+                entity,
+                level: level.0,
+            });
+        }
+    }
+}
+
+pub fn announce_level_ups(mut level_ups: EventReader<LevelUpEvent>) {
+    for event in level_ups.iter() {
+        info!("{:?} reached level {}", event.entity, event.level);
+    }
+}
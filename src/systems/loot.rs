@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::components::enemy::EnemyKind;
+use crate::components::inventory::Inventory;
+use crate::components::item::ItemPickup;
+use crate::components::{BoxCollider, ColliderType, CollisionLayer, FloorEntity, LayerMask};
+use crate::events::{DeathEvent, ItemPickedUp, TriggerEntered};
+use crate::resources::items::{ItemDatabase, ItemDef};
+use crate::resources::loot::LootTables;
+use crate::resources::TILE_SIZE;
+use crate::systems::dungeon_gen::Dungeon;
+use crate::MyAssets;
+
+// the prop icons are a full 32x32 tile, smaller reads better as something to pick up
+const PICKUP_SCALE: f32 = 0.6;
+const PICKUP_SIZE: f32 = 16.0;
+// how far apart the drops of one enemy land
+const DROP_SPREAD: f32 = 10.0;
+// keeps the room loot from rolling the same numbers as the enemies of the same seed
+const ROOM_LOOT_SEED: u64 = 0x6c6f6f74;
+
+/*
+Put item on the floor at position, as a trigger only the player can set off
+ */
+pub fn spawn_pickup(commands: &mut Commands, assets: &MyAssets, item: &ItemDef, position: Vec2) -> Entity {
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            texture_atlas: assets.items.clone(),
+            sprite: TextureAtlasSprite::new(item.icon),
+            // under the player and enemies
+            transform: Transform::from_translation(position.extend(0.5)).with_scale(Vec3::splat(PICKUP_SCALE)),
+            ..Default::default()
+        })
+        .insert(FloorEntity)
+        .insert(ItemPickup {
+            item: item.id.clone(),
+        })
+        .insert(crate::components::person::Name(item.name.clone()))
+        .insert(BoxCollider {
+            width: PICKUP_SIZE,
+            height: PICKUP_SIZE,
+            membership: LayerMask::from_layers(&[CollisionLayer::Pickup]),
+            collision_mask: LayerMask::from_layers(&[CollisionLayer::Player]),
+            offset: Vec2::ZERO,
+            scale: Vec2::ONE,
+            collider_type: ColliderType::Trigger,
+        })
+        .id()
+}
+
+/*
+Scatter the loot of every room's drop table over the room
+Like the enemies, the same seed always has the same loot lying around
+ */
+pub fn spawn_room_loot(
+    mut commands: Commands,
+    dungeon: Res<Dungeon>,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    loot_tables: Res<Assets<LootTables>>,
+) {
+    let (items, loot_tables) = match (items.get(&assets.item_database), loot_tables.get(&assets.loot_tables)) {
+        (Some(items), Some(loot_tables)) => (items, loot_tables),
+        _ => {
+            warn!("Items or loot tables are not loaded, no loot spawned");
+            return;
+        }
+    };
+
+    let mut rng = StdRng::seed_from_u64(dungeon.seed ^ ROOM_LOOT_SEED);
+    let mut count = 0;
+
+    for (index, room) in dungeon.rooms.iter().enumerate() {
+        let table = match loot_tables.rooms.get(&dungeon.room_kind(index)) {
+            Some(table) => table,
+            None => continue,
+        };
+
+        for id in table.roll(&mut rng) {
+            let tile = room.random_tile(&mut rng);
+            match items.get(id) {
+                Some(item) => {
+                    spawn_pickup(&mut commands, &assets, item, Vec2::new(tile.x as f32 * TILE_SIZE.x, tile.y as f32 * TILE_SIZE.y));
+                    count += 1;
+                }
+                None => warn!("Loot table has unknown item {}", id),
+            }
+        }
+    }
+
+    info!("Spawned {} items", count);
+}
+
+/*
+Roll the drop table of every enemy that died, its loot lands around where it fell
+ */
+pub fn drop_enemy_loot(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    loot_tables: Res<Assets<LootTables>>,
+    enemies: Query<(&EnemyKind, &Transform)>,
+) {
+    let (items, loot_tables) = match (items.get(&assets.item_database), loot_tables.get(&assets.loot_tables)) {
+        (Some(items), Some(loot_tables)) => (items, loot_tables),
+        _ => return,
+    };
+    let mut rng = rand::thread_rng();
+
+    for death in deaths.iter() {
+        let (kind, transform) = match enemies.get(death.entity) {
+            Ok(enemy) => enemy,
+            Err(_) => continue,
+        };
+        let table = match loot_tables.enemies.get(kind) {
+            Some(table) => table,
+            None => continue,
+        };
+
+        for id in table.roll(&mut rng) {
+            let offset = Vec2::new(rng.gen_range(-DROP_SPREAD..DROP_SPREAD), rng.gen_range(-DROP_SPREAD..DROP_SPREAD));
+            match items.get(id) {
+                Some(item) => {
+                    spawn_pickup(&mut commands, &assets, item, transform.translation.truncate() + offset);
+                }
+                None => warn!("Loot table has unknown item {}", id),
+            }
+        }
+    }
+}
+
+/*
+Walking over an item puts it in the inventory, if there's room for it
+A full inventory leaves it on the floor, to try again the player has to step off and back on
+ */
+pub fn collect_pickups(
+    mut commands: Commands,
+    mut trigger_entered: EventReader<TriggerEntered>,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+    pickups: Query<&ItemPickup>,
+    mut inventories: Query<&mut Inventory>,
+    mut picked_up: EventWriter<ItemPickedUp>,
+) {
+    let items = match items.get(&assets.item_database) {
+        Some(items) => items,
+        None => return,
+    };
+
+    for event in trigger_entered.iter() {
+        let (pickup, mut inventory) = match (pickups.get(event.trigger), inventories.get_mut(event.other)) {
+            (Ok(pickup), Ok(inventory)) => (pickup, inventory),
+            _ => continue,
+        };
+        let item = match items.get(&pickup.item) {
+            Some(item) => item,
+            None => continue,
+        };
+
+        if inventory.add(item, 1) > 0 {
+            info!("No room for {}", item.name);
+            continue;
+        }
+        picked_up.send(ItemPickedUp {
+            entity: event.other,
+            item: pickup.item.clone(),
+        });
+        commands.entity(event.trigger).despawn_recursive();
+    }
+}
+
+// say what was picked up, by its name in the item database
+pub fn announce_pickups(
+    mut picked_up: EventReader<ItemPickedUp>,
+    assets: Res<MyAssets>,
+    items: Res<Assets<ItemDatabase>>,
+) {
+    let items = match items.get(&assets.item_database) {
+        Some(items) => items,
+        None => return,
+    };
+
+    for event in picked_up.iter() {
+        let name = items.get(&event.item).map_or(event.item.as_str(), |item| item.name.as_str());
+        info!("{:?} picked up {}", event.entity, name);
+    }
+}
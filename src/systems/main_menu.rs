@@ -0,0 +1,368 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::window::{PresentMode, WindowMode};
+
+use crate::components::main_menu::{MainMenuRoot, MenuButton};
+use crate::components::{FloorEntity, GameEntity};
+use crate::events::NewGameStarted;
+use crate::resources::input::{ActionInput, InputAction, InputBindings};
+use crate::resources::inventory::InventoryUi;
+use crate::resources::main_menu::{MainMenuUi, MenuAction, MenuScreen, MAX_SEED_DIGITS};
+use crate::resources::MyStates;
+use crate::systems::dungeon_gen::Dungeon;
+use crate::systems::world_gen::ChunkManager;
+use crate::MyAssets;
+
+const TITLE_FONT_SIZE: f32 = 48.0;
+const MENU_FONT_SIZE: f32 = 40.0;
+const HINT_FONT_SIZE: f32 = 26.0;
+// the controls page has a row for every action, smaller so they all fit
+const CONTROLS_FONT_SIZE: f32 = 28.0;
+const SELECTED_COLOR: Color = Color::YELLOW;
+const DISABLED_COLOR: Color = Color::GRAY;
+const SELECTED_BACKGROUND: Color = Color::rgba(1.0, 1.0, 1.0, 0.08);
+
+// a game is running under the menu, so there is something to continue
+fn in_game(state: &State<MyStates>) -> bool {
+    state.inactives().contains(&MyStates::Game)
+}
+
+fn vsync(window: &Window) -> bool {
+    matches!(window.present_mode(), PresentMode::AutoVsync | PresentMode::Fifo)
+}
+
+// keys then gamepad buttons bound to action, like "W, Up / DPadUp"
+fn bound_to(bindings: &InputBindings, action: InputAction) -> String {
+    let keys: Vec<_> = bindings.keys.get(&action).into_iter().flatten().map(|key| format!("{:?}", key)).collect();
+    let buttons: Vec<_> = bindings
+        .gamepad_buttons
+        .get(&action)
+        .into_iter()
+        .flatten()
+        .map(|button| format!("{:?}", button))
+        .collect();
+    format!("{} / {}", keys.join(", "), buttons.join(", "))
+}
+
+/*
+The main menu opens on its first page, with Continue picked when it was opened from a game
+ */
+pub fn open_main_menu(mut ui: ResMut<MainMenuUi>, state: Res<State<MyStates>>) {
+    ui.show(MenuScreen::Main);
+    if in_game(&state) {
+        ui.selected = MenuScreen::Main.actions().iter().position(|action| *action == MenuAction::Continue).unwrap_or(0);
+    }
+}
+
+pub fn close_main_menu(mut commands: Commands, roots: Query<Entity, With<MainMenuRoot>>) {
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+}
+
+/*
+Pause opens the main menu over the game, the game stays as it is until Continue goes back to it
+ */
+pub fn pause_game(mut actions: ResMut<ActionInput>, mut app_state: ResMut<State<MyStates>>) {
+    if actions.consume(InputAction::Pause) {
+        app_state.push(MyStates::MainMenu).unwrap_or_else(|e| error!("Failed to open the main menu: {:?}", e));
+    }
+}
+
+/*
+Move actions and the mouse pick a row, interact, enter or a click does what it says
+Pause goes back a page, or back to the game from the first one
+While an action is being rebound the next key or button pressed is bound to it instead,
+escape leaves its bindings as they were
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_main_menu(
+    mut actions: ResMut<ActionInput>,
+    keys: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut ui: ResMut<MainMenuUi>,
+    mut bindings: ResMut<InputBindings>,
+    mut app_state: ResMut<State<MyStates>>,
+    mut dungeon: ResMut<Dungeon>,
+    mut windows: ResMut<Windows>,
+    mut new_games: EventWriter<NewGameStarted>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if let Some(action) = ui.rebinding {
+        if keys.just_pressed(KeyCode::Escape) {
+            ui.rebinding = None;
+        } else if let Some(key) = keys.get_just_pressed().next() {
+            bindings.rebind_key(action, *key);
+            ui.rebinding = None;
+        } else if let Some(button) = gamepad_buttons.get_just_pressed().next() {
+            bindings.rebind_button(action, button.button_type);
+            ui.rebinding = None;
+        }
+        return;
+    }
+
+    let rows = ui.screen.actions().len();
+    if actions.just_pressed(InputAction::MoveUp) && ui.selected > 0 {
+        ui.selected -= 1;
+    }
+    if actions.just_pressed(InputAction::MoveDown) && ui.selected + 1 < rows {
+        ui.selected += 1;
+    }
+
+    let mut picked = None;
+    for (interaction, button) in buttons.iter() {
+        match interaction {
+            Interaction::Hovered if ui.selected != button.index => ui.selected = button.index,
+            Interaction::Clicked => {
+                ui.selected = button.index;
+                picked = Some(button.action);
+            }
+            _ => {}
+        }
+    }
+    if actions.consume(InputAction::Interact) || keys.just_pressed(KeyCode::Return) {
+        picked = ui.selected_action();
+    }
+    if actions.consume(InputAction::Pause) {
+        picked = Some(match ui.screen {
+            MenuScreen::Main => MenuAction::Continue,
+            _ => MenuAction::Back,
+        });
+    }
+
+    match picked {
+        Some(MenuAction::NewGame) => ui.show(MenuScreen::NewGame),
+        // greyed out when there's no game under the menu
+        Some(MenuAction::Continue) if in_game(&app_state) => {
+            app_state.pop().unwrap_or_else(|e| error!("Failed to go back to the game: {:?}", e));
+        }
+        Some(MenuAction::Settings) => ui.show(MenuScreen::Settings),
+        Some(MenuAction::Quit) => exit.send(AppExit),
+        // enter on the seed field is as good as picking Start
+        Some(MenuAction::Seed | MenuAction::Start) => {
+            *dungeon = Dungeon::with_seed(ui.parsed_seed().unwrap_or_else(rand::random));
+            new_games.send(NewGameStarted);
+            // replace unwinds a game paused under the menu before going on
+            app_state
+                .replace(MyStates::DungeonGeneration)
+                .unwrap_or_else(|e| error!("Failed to start a new game: {:?}", e));
+        }
+        Some(MenuAction::ToggleFullscreen) => {
+            if let Some(window) = windows.get_primary_mut() {
+                window.set_mode(match window.mode() {
+                    WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+                    _ => WindowMode::Windowed,
+                });
+                ui.set_changed();
+            }
+        }
+        Some(MenuAction::ToggleVsync) => {
+            if let Some(window) = windows.get_primary_mut() {
+                window.set_present_mode(if vsync(window) { PresentMode::AutoNoVsync } else { PresentMode::AutoVsync });
+                ui.set_changed();
+            }
+        }
+        Some(MenuAction::Controls) => ui.show(MenuScreen::Controls),
+        Some(MenuAction::Rebind(action)) => ui.rebinding = Some(action),
+        Some(MenuAction::ResetControls) => {
+            *bindings = InputBindings::default();
+            ui.set_changed();
+        }
+        Some(MenuAction::Back) => {
+            let back = ui.screen.back();
+            ui.show(back);
+        }
+        Some(MenuAction::Continue) | None => {}
+    }
+}
+
+/*
+Digits typed on the new game page go into the seed, backspace takes the last one off
+ */
+pub fn type_seed(mut typed: EventReader<ReceivedCharacter>, keys: Res<Input<KeyCode>>, mut ui: ResMut<MainMenuUi>) {
+    if ui.screen != MenuScreen::NewGame {
+        typed.clear();
+        return;
+    }
+
+    for character in typed.iter() {
+        if character.char.is_ascii_digit() && ui.seed.len() < MAX_SEED_DIGITS {
+            ui.seed.push(character.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        ui.seed.pop();
+    }
+}
+
+/*
+Rebuild the menu whenever it is opened or anything it shows changes
+ */
+pub fn draw_main_menu(
+    mut commands: Commands,
+    assets: Res<MyAssets>,
+    ui: Res<MainMenuUi>,
+    bindings: Res<InputBindings>,
+    state: Res<State<MyStates>>,
+    windows: Res<Windows>,
+    roots: Query<Entity, With<MainMenuRoot>>,
+) {
+    if !ui.is_changed() {
+        return;
+    }
+    for root in roots.iter() {
+        commands.entity(root).despawn_recursive();
+    }
+
+    let (title, hint) = match ui.screen {
+        MenuScreen::Main => ("Main Menu", ""),
+        MenuScreen::NewGame => ("New Game", "Type a seed, or leave it empty for a random one"),
+        MenuScreen::Settings => ("Settings", ""),
+        MenuScreen::Controls => ("Controls", "Pick an action, then press the key or button for it"),
+    };
+    let (font_size, row_width) = match ui.screen {
+        MenuScreen::Controls => (CONTROLS_FONT_SIZE, 640.0),
+        _ => (MENU_FONT_SIZE, 420.0),
+    };
+    let window = windows.get_primary();
+    let on_off = |on: bool| if on { "On" } else { "Off" };
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                // top to bottom
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            // covers whatever game is paused under it
+            color: Color::rgba(0.02, 0.02, 0.04, 0.92).into(),
+            ..default()
+        })
+        .insert(MainMenuRoot)
+        .with_children(|menu| {
+            menu.spawn_bundle(
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font: assets.hud_title_font.clone(),
+                        font_size: TITLE_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect {
+                        bottom: Val::Px(30.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            );
+
+            for (index, action) in ui.screen.actions().iter().enumerate() {
+                let selected = index == ui.selected;
+                let label = match action {
+                    MenuAction::NewGame => "New Game".to_string(),
+                    MenuAction::Continue => "Continue".to_string(),
+                    MenuAction::Settings => "Settings".to_string(),
+                    MenuAction::Quit => "Quit".to_string(),
+                    MenuAction::Seed if ui.seed.is_empty() && !selected => "Seed: random".to_string(),
+                    MenuAction::Seed => format!("Seed: {}{}", ui.seed, if selected { "_" } else { "" }),
+                    MenuAction::Start => "Start".to_string(),
+                    MenuAction::ToggleFullscreen => {
+                        format!("Fullscreen: {}", on_off(window.is_some_and(|window| window.mode() != WindowMode::Windowed)))
+                    }
+                    MenuAction::ToggleVsync => format!("VSync: {}", on_off(window.is_some_and(vsync))),
+                    MenuAction::Controls => "Controls".to_string(),
+                    MenuAction::Rebind(action) if ui.rebinding == Some(*action) => {
+                        format!("{}: press a key or button, escape to cancel", action.name())
+                    }
+                    MenuAction::Rebind(action) => format!("{}: {}", action.name(), bound_to(&bindings, *action)),
+                    MenuAction::ResetControls => "Reset to defaults".to_string(),
+                    MenuAction::Back => "Back".to_string(),
+                };
+                let color = if *action == MenuAction::Continue && !in_game(&state) {
+                    DISABLED_COLOR
+                } else if selected {
+                    SELECTED_COLOR
+                } else {
+                    Color::WHITE
+                };
+
+                menu.spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(row_width), Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    color: if selected { SELECTED_BACKGROUND } else { Color::NONE }.into(),
+                    ..default()
+                })
+                .insert(MenuButton { action: *action, index })
+                .with_children(|button| {
+                    button.spawn_bundle(TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font: assets.hud_font.clone(),
+                            font_size,
+                            color,
+                        },
+                    ));
+                });
+            }
+
+            if !hint.is_empty() {
+                menu.spawn_bundle(
+                    TextBundle::from_section(
+                        hint,
+                        TextStyle {
+                            font: assets.hud_font.clone(),
+                            font_size: HINT_FONT_SIZE,
+                            color: DISABLED_COLOR,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect {
+                            top: Val::Px(20.0),
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                );
+            }
+        });
+}
+
+/*
+The game is left to go down to the next floor, or for a new game started over the old one
+Either way the floor goes, a new game takes the player, the HUD and everything else that
+belonged to the old game with it
+Whatever isn't tagged as part of the game or its floor, like the camera and the menu, stays
+ */
+pub fn clear_game(
+    mut commands: Commands,
+    new_games: EventReader<NewGameStarted>,
+    floor_entities: Query<Entity, (Without<Parent>, With<FloorEntity>)>,
+    game_entities: Query<Entity, (Without<Parent>, With<GameEntity>)>,
+    mut chunk_manager: ResMut<ChunkManager>,
+    mut inventory_ui: ResMut<InventoryUi>,
+) {
+    for entity in floor_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    chunk_manager.spawned_chunks.clear();
+
+    if new_games.is_empty() {
+        return;
+    }
+    new_games.clear();
+    for entity in game_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *inventory_ui = InventoryUi::default();
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::components::player::PlayerAnimationState;
+use crate::components::status::{EffectKind, StatusEffects};
+use crate::components::{FloorEntity, MapMarker, MapOverlay};
+use crate::resources::TILE_SIZE;
+use crate::systems::dungeon_gen::Dungeon;
+
+// pixels on screen for every tile
+const MAP_SCALE: f32 = 2.0;
+const MAP_MARKER_SIZE: f32 = 6.0;
+const MAP_FLOOR: [u8; 4] = [170, 165, 150, 255];
+const MAP_STAIRS: [u8; 4] = [240, 200, 60, 255];
+
+/*
+One pixel for every tile of the dungeon, floor is drawn and everything else is left see-through
+ */
+fn map_image(dungeon: &Dungeon) -> Image {
+    let size = dungeon.size();
+    let stairs = Dungeon::world_to_tile(dungeon.stairs_point());
+    let mut data = vec![0; (size.x * size.y * 4) as usize];
+
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let tile = IVec2::new(x as i32, y as i32);
+            let color = if (tile - stairs).abs().max_element() <= 1 {
+                MAP_STAIRS
+            } else if !dungeon.is_wall(tile.x, tile.y) {
+                MAP_FLOOR
+            } else {
+                continue;
+            };
+
+            // image rows go down, tile rows go up
+            let index = (((size.y - 1 - y) * size.x + x) * 4) as usize;
+            data[index..index + 4].copy_from_slice(&color);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+// where on the map the marker for a world position goes
+fn marker_position(position: Vec2) -> UiRect<Val> {
+    let tile = position / Vec2::new(TILE_SIZE.x, TILE_SIZE.y) + Vec2::splat(0.5);
+    UiRect {
+        left: Val::Px(tile.x * MAP_SCALE - MAP_MARKER_SIZE / 2.0),
+        bottom: Val::Px(tile.y * MAP_SCALE - MAP_MARKER_SIZE / 2.0),
+        ..default()
+    }
+}
+
+/*
+Show the whole floor with the player on it for as long as the player is under RevealMap
+ */
+#[allow(clippy::type_complexity)]
+pub fn show_map_overlay(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    dungeon: Res<Dungeon>,
+    player: Query<(&Transform, &StatusEffects), With<PlayerAnimationState>>,
+    overlays: Query<Entity, With<MapOverlay>>,
+    mut markers: Query<&mut Style, With<MapMarker>>,
+) {
+    let (transform, effects) = match player.get_single() {
+        Ok(player) => player,
+        Err(_) => return,
+    };
+    let position = transform.translation.truncate();
+
+    match (effects.has(&EffectKind::RevealMap), overlays.get_single()) {
+        (true, Ok(_)) => {
+            for mut style in markers.iter_mut() {
+                style.position = marker_position(position);
+            }
+        }
+        (true, Err(_)) => {
+            let size = dungeon.size().as_vec2() * MAP_SCALE;
+            commands
+                .spawn_bundle(ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(size.x), Val::Px(size.y)),
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            bottom: Val::Px(15.0),
+                            right: Val::Px(15.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    image: UiImage(images.add(map_image(&dungeon))),
+                    color: Color::rgba(1.0, 1.0, 1.0, 0.85).into(),
+                    ..default()
+                })
+                .insert(MapOverlay)
+                .insert(FloorEntity)
+                .with_children(|map| {
+                    map.spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(MAP_MARKER_SIZE), Val::Px(MAP_MARKER_SIZE)),
+                            position_type: PositionType::Absolute,
+                            position: marker_position(position),
+                            ..default()
+                        },
+                        color: Color::RED.into(),
+                        ..default()
+                    })
+                    .insert(MapMarker);
+                });
+        }
+        // the image goes with the last handle to it
+        (false, Ok(overlay)) => commands.entity(overlay).despawn_recursive(),
+        (false, Err(_)) => {}
+    }
+}
@@ -0,0 +1,175 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::components::ai::{Ai, AiState, CachedPath};
+use crate::components::enemy::Enemy;
+use crate::systems::dungeon_gen::Dungeon;
+
+// cost of a straight and a diagonal step, roughly 1 and sqrt(2)
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+// close enough to a waypoint to move on to the next one
+const WAYPOINT_DISTANCE: f32 = 4.0;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/*
+Octile distance, the cost of the best path if there were no walls at all
+ */
+fn heuristic(from: IVec2, to: IVec2) -> u32 {
+    let d = (to - from).abs();
+    let (short, long) = (d.x.min(d.y) as u32, d.x.max(d.y) as u32);
+    DIAGONAL_COST * short + STRAIGHT_COST * (long - short)
+}
+
+/*
+A* over the dungeon tiles, walls are blocked
+Diagonal steps are allowed, but not past the corner of a wall, so a body wider than a point
+does not get caught on it
+Returns every tile from start to goal (both included), or None if goal can't be reached
+ */
+pub fn find_path(dungeon: &Dungeon, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+    if dungeon.is_wall(start.x, start.y) || dungeon.is_wall(goal.x, goal.y) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::default();
+    let mut cost: HashMap<IVec2, u32> = HashMap::default();
+
+    cost.insert(start, 0);
+    open.push(Reverse((heuristic(start, goal), 0, (start.x, start.y))));
+
+    while let Some(Reverse((_, current_cost, (x, y)))) = open.pop() {
+        let current = IVec2::new(x, y);
+        if current == goal {
+            let mut path = vec![goal];
+            let mut tile = goal;
+            while let Some(&previous) = came_from.get(&tile) {
+                path.push(previous);
+                tile = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        // already reached this tile a cheaper way
+        if current_cost > cost[&current] {
+            continue;
+        }
+
+        for offset in NEIGHBOURS {
+            let next = current + offset;
+            if dungeon.is_wall(next.x, next.y) {
+                continue;
+            }
+
+            let diagonal = offset.x != 0 && offset.y != 0;
+            if diagonal && (dungeon.is_wall(current.x + offset.x, current.y) || dungeon.is_wall(current.x, current.y + offset.y)) {
+                continue;
+            }
+
+            let next_cost = current_cost + if diagonal { DIAGONAL_COST } else { STRAIGHT_COST };
+            if cost.get(&next).is_none_or(|&known| next_cost < known) {
+                cost.insert(next, next_cost);
+                came_from.insert(next, current);
+                open.push(Reverse((next_cost + heuristic(next, goal), next_cost, (next.x, next.y))));
+            }
+        }
+    }
+
+    None
+}
+
+/*
+Steer enemies that are heading somewhere (chasing or wandering) along a path around the walls
+A straight line is used while nothing is in the way, the path is only worked out when it is
+needed and is worked out again every so often, since the player keeps moving
+Searches are spaced out by the repath timer, so an enemy that can't get there isn't running A*
+every frame
+ */
+pub fn follow_paths(
+    time: Res<Time>,
+    dungeon: Res<Dungeon>,
+    mut enemies: Query<(&Transform, &mut Ai, &mut CachedPath), With<Enemy>>,
+) {
+    for (transform, mut ai, mut path) in enemies.iter_mut() {
+        path.repath.tick(time.delta());
+
+        let target = match (ai.state, ai.target) {
+            (AiState::Chase | AiState::Wander, Some(target)) => target,
+            _ => {
+                path.clear();
+                continue;
+            }
+        };
+
+        let position = transform.translation.truncate();
+        if dungeon.line_of_sight(position, target) {
+            path.clear();
+            continue;
+        }
+
+        let goal = Dungeon::world_to_tile(target);
+        // a goal that can't be reached is only tried again once the timer runs out, like a moving one
+        if path.repath.finished() && (path.waypoints.is_empty() || path.goal != goal) {
+            path.repath.reset();
+            path.goal = goal;
+            path.waypoints = find_path(&dungeon, Dungeon::world_to_tile(position), goal)
+                .map(|tiles| tiles.into_iter().skip(1).map(Dungeon::tile_to_world).collect())
+                .unwrap_or_default();
+        }
+
+        while let Some(waypoint) = path.waypoints.first() {
+            if waypoint.distance(position) > WAYPOINT_DISTANCE {
+                break;
+            }
+            path.waypoints.remove(0);
+        }
+
+        // no way there, or already there
+        ai.move_direction = match path.waypoints.first() {
+            Some(waypoint) => (*waypoint - position).normalize_or_zero(),
+            None => Vec2::ZERO,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_room_reaches_every_other_room() {
+        for seed in 0..3 {
+            let dungeon = Dungeon::generate(128, 128, seed);
+
+            for (from, room) in dungeon.rooms.iter().enumerate() {
+                for (to, other) in dungeon.rooms.iter().enumerate().skip(from + 1) {
+                    let (start, goal) = (room.center().as_ivec2(), other.center().as_ivec2());
+                    let path = find_path(&dungeon, start, goal)
+                        .unwrap_or_else(|| panic!("room {} can't reach room {} with seed {}", from, to, seed));
+
+                    assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+                    for step in path.windows(2) {
+                        assert!(!dungeon.is_wall(step[1].x, step[1].y), "path goes through a wall with seed {}", seed);
+                        assert_eq!((step[1] - step[0]).abs().max_element(), 1, "path skips a tile with seed {}", seed);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use crate::components::person::{Name, Person};
 use crate::resources::GreetTimer;
 
+#[allow(dead_code)]
 pub fn add_people(mut commands: Commands) {
     commands
         .spawn()
@@ -17,6 +18,7 @@ pub fn add_people(mut commands: Commands) {
         .insert(Name("Natalie".to_string()));
 }
 
+#[allow(dead_code)]
 pub fn greet_people(
     time: Res<Time>, mut timer: ResMut<GreetTimer>, query: Query<&Name, With<Person>>) {
     // update our timer with the time elapsed since the last update
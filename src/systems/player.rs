@@ -1,27 +1,68 @@
 use bevy::prelude::*;
-use crate::components::{AnimationTimer, Health};
-use crate::components::player::{PlayerAnimationState, PlayerAnimationStates, PlayerBundle, PlayerXp};
-use crate::{BoxCollider, Collision, MyAssets};
-use crate::systems::world_gen::get_center_of_world;
+use crate::components::{CircleCollider, CollisionLayer, GameEntity, Health, Invulnerable, LayerMask, Stamina};
+use crate::components::animation::Animator;
+use crate::components::combat::{MeleeAttack, Shield};
+use crate::components::inventory::{EquipmentBonus, Inventory};
+use crate::components::player::{DodgeRoll, PlayerAnimationState, PlayerAnimationStates, PlayerBundle, PlayerLevel, PlayerXp};
+use crate::components::stats::Stats;
+use crate::components::status::StatusEffects;
+use crate::{BoxCollider, MyAssets};
+use crate::systems::collision::{blocking_solids, depenetrate, sweep_aabb, SolidGrid};
+use crate::systems::dungeon_gen::Dungeon;
+use crate::resources::input::{ActionInput, InputAction};
+use crate::resources::inventory::InventoryUi;
 
+const PLAYER_COLLISION_MASK: LayerMask = LayerMask::from_layers(&[
+    CollisionLayer::Enemy,
+    CollisionLayer::Wall,
+    CollisionLayer::Projectile,
+    CollisionLayer::Pickup,
+    CollisionLayer::Trigger,
+]);
+
+// units per second
+const WALK_SPEED: f32 = 100.0;
+const RUN_SPEED: f32 = 170.0;
+const BLOCK_SPEED: f32 = 40.0;
+const DODGE_SPEED: f32 = 320.0;
+// seconds the roll lasts, the player is invulnerable for all of it
+const DODGE_TIME: f32 = 0.25;
+const DODGE_COST: f32 = 25.0;
+// stamina per second of running
+const RUN_COST: f32 = 20.0;
+// stacks of items the player can carry
+const INVENTORY_CAPACITY: usize = 12;
+
+/*
+Put the player in the first room of the floor
+The player from the floor above keeps going with everything they had, only a new game makes a new one
+ */
+#[allow(clippy::clone_on_copy)]
 pub fn create_player (
     mut commands: Commands,
     assets: Res<MyAssets>,
     texture_atlases: Res<Assets<TextureAtlas>>,
+    dungeon: Res<Dungeon>,
+    mut players: Query<&mut Transform, With<PlayerAnimationState>>,
 ) {
     let texture_atlas_handle = texture_atlases.get_handle(&assets.player);
 
-    // center of map
-    let center_of_world = get_center_of_world();
+    // start in the first room of the dungeon
+    let spawn_point = dungeon.spawn_point();
 
-    let transform = Transform::from_translation(Vec3::new(center_of_world.x, center_of_world.y, 1.0));
+    let transform = Transform::from_translation(Vec3::new(spawn_point.x, spawn_point.y, 1.0));
+    if let Ok(mut player) = players.get_single_mut() {
+        *player = transform;
+        info!("Player moved down to floor {}", dungeon.floor);
+        return;
+    }
 
     let mut player = PlayerBundle {
-        health: Health {
-            hp: 100.0,
-            armor: 0.0,
-        },
+        health: Health::new(100.0, 0.0),
+        stamina: Stamina::new(100.0, 30.0, 0.75),
         xp: PlayerXp(0),
+        level: PlayerLevel(1),
+        stats: Stats::default(),
         name: crate::components::person::Name("Player".to_string()),
         animation: PlayerAnimationState(PlayerAnimationStates::Idle),
         sprite: SpriteSheetBundle {
@@ -38,66 +79,60 @@ pub fn create_player (
         collider: crate::components::BoxCollider {
             width: 20.0,
             height: 20.0,
-            layer: 0,
+            membership: LayerMask::from_layers(&[CollisionLayer::Player]),
+            collision_mask: PLAYER_COLLISION_MASK,
             offset: Vec2::new(0.0, 0.0),
             scale: Vec2::new(1.0, 1.0),
             collider_type: crate::components::ColliderType::Solid,
         },
     };
     player.sprite.sprite.index = 0;
-    commands.spawn_bundle(player).insert(AnimationTimer(Timer::from_seconds(0.2, true)));
+    commands
+        .spawn_bundle(player)
+        .insert(Animator::new("player", "idle"))
+        // the swing lasts as long as the 8 frame attack clip
+        .insert(MeleeAttack::new(10.0, 200.0, 0.5, 0.6))
+        .insert(Shield::new(0.6, 2.0, 90.0))
+        .insert(Inventory::new(INVENTORY_CAPACITY))
+        .insert(EquipmentBonus::default())
+        .insert(StatusEffects::default())
+        .insert(GameEntity);
 
     info!("Player spawned and setup")
 }
 
 /*
-Animate based on the timer and PlayerAnimationState value of the entity
-different PlayerAnimation variants have different numbers of frames
-we use the number of frames to determine which frame to show
-Idle: frames 0-1 (2 frames)
-Blink: frames 9-10 (2 frames)
-Walk: frame 18-21 (4 frames)
-Run: frame 27-34 (8 frames)
+Play the clip for whatever the player is doing, the frames themselves are in animations.anim.ron
+The jump frames double as the dodge-roll, crouching as blocking, and a swing plays over any movement
  */
 pub fn animate_player (
-    time: Res<Time>,
-    mut query: Query<(&mut TextureAtlasSprite, &mut AnimationTimer, &PlayerAnimationState)>,
+    mut query: Query<(&mut Animator, &PlayerAnimationState, Option<&MeleeAttack>)>,
 ) {
-    for (mut sprite, mut timer, animation) in query.iter_mut() {
-        timer.0.tick(time.delta());
-        if timer.0.finished() {
-            match animation.0 {
-                PlayerAnimationStates::Idle => {
-                    sprite.index = (sprite.index + 1) % 2;
-                }
-                PlayerAnimationStates::Blink => {
-                    sprite.index = (sprite.index + 1) % 2 + 9;
-                }
-                PlayerAnimationStates::Walk => {
-                    sprite.index = (sprite.index + 1) % 4 + 18;
-                }
-                PlayerAnimationStates::Run => {
-                    sprite.index = (sprite.index + 1) % 8 + 27;
-                }
-                PlayerAnimationStates::Jump => {
-                    sprite.index = 35;
-                }
-                PlayerAnimationStates::Duck => {
-                    sprite.index = 36;
-                }
-            }
+    for (mut animator, animation, attack) in query.iter_mut() {
+        if attack.is_some_and(|attack| attack.is_swinging()) {
+            animator.play("attack");
+            continue;
         }
+
+        animator.play(match animation.0 {
+            PlayerAnimationStates::Idle => "idle",
+            PlayerAnimationStates::Blink => "idle2",
+            PlayerAnimationStates::Walk => "walk",
+            PlayerAnimationStates::Run => "run",
+            PlayerAnimationStates::Jump => "jump",
+            PlayerAnimationStates::Duck => "crouch",
+        });
     }
 }
 
 /*
 Using this for debugging
 This will draw all the sprites in the texture atlas in a grid
-8 rows and 9 columns of 32x32 sprites
+9 rows and 8 columns of 32x32 sprites
 start at the top left and go right then down
  */
 
-#[allow(dead_code)]
+#[allow(dead_code, clippy::unnecessary_cast)]
 fn draw_all_sprites(
     mut commands: Commands,
     my_assets: Res<MyAssets>,
@@ -130,82 +165,118 @@ fn draw_all_sprites(
 }
 
 /*
-Moving the player should get the keybaord input and move the player sprite
-It should also change the animation state to walking if the player is moving
-Prevent movement if colliding with a solid object
+Moving the player should get the movement input and move the player sprite
+Holding run moves faster while it drains stamina, dodging spends a chunk of stamina to roll
+a short distance, invulnerable, in the direction being moved (or faced, when standing still)
+Holding block slows the player right down and keeps them facing the same way
+Every speed goes up with Dexterity
+The animation state follows whichever of these the player is actually doing
+Movement is swept against the bounds of the solid colliders around the player, so the player
+stops flush against them instead of inside of them
 Lastly, when going left, the player should flip the sprite
  */
+#[allow(clippy::type_complexity)]
 pub fn move_player(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&mut Transform, &mut PlayerAnimationState, &Collision, &mut TextureAtlasSprite)>,
-    entities: Query<(Entity, &BoxCollider, &Transform), Without<PlayerAnimationState>>,
+    mut commands: Commands,
+    actions: Res<ActionInput>,
+    mut query: Query<(
+        Entity,
+        &mut Transform,
+        &mut PlayerAnimationState,
+        &BoxCollider,
+        &mut TextureAtlasSprite,
+        &mut Stamina,
+        Option<&mut DodgeRoll>,
+        Option<&Shield>,
+        Option<&Stats>,
+    )>,
+    entities: Query<(Option<&BoxCollider>, Option<&CircleCollider>, &Transform), Without<PlayerAnimationState>>,
+    mut solid_grid: ResMut<SolidGrid>,
+    inventory_ui: Res<InventoryUi>,
     time: Res<Time>
 ) {
-    for (mut transform, mut animation, collision, mut sprite) in query.iter_mut() {
-        let collisions: Vec<u32> = collision.collisions.clone();
-        let mut direction = Vec3::ZERO;
-        if keyboard_input.pressed(KeyCode::Left) {
-            direction.x -= 1.0;
-            // flip the sprite
-            sprite.flip_x = true;
-        }
-        if keyboard_input.pressed(KeyCode::Right) {
-            direction.x += 1.0;
-            // flip the sprite
-            sprite.flip_x = false;
-        }
-        if keyboard_input.pressed(KeyCode::Up) {
-            direction.y += 1.0;
-        }
-        if keyboard_input.pressed(KeyCode::Down) {
-            direction.y -= 1.0;
-        }
-
-        // if the player is colliding with a solid object, don't move in the direction of the collision
-        if collision.is_colliding() {
-            for (e, collider, other_transform) in entities.iter() {
-                if collisions.contains(&e.id()) {
-                    if collider.collider_type == crate::components::ColliderType::Solid {
-                        // if the player is colliding with a solid object, don't move in the direction of the collision
-                        // they will be able to move in the other directions
-
-
-                        if (transform.translation.x + direction.x) < other_transform.translation.x {
-                            // if the player is moving towards the solid object, don't move
-                            if direction.x > 0.0 {
-                                direction.x = 0.0;
-                            }
-                        }
-                        else if (transform.translation.x + direction.x) > other_transform.translation.x {
-                            // if the player is moving towards the solid object, don't move
-                            if direction.x < 0.0 {
-                                direction.x = 0.0;
-                            }
-                        }
-
-                        if (transform.translation.y + direction.y) < other_transform.translation.y {
-                            // if the player is moving towards the solid object, don't move
-                            if direction.y > 0.0 {
-                                direction.y = 0.0;
-                            }
-                        }
-                        else if (transform.translation.y + direction.y) > other_transform.translation.y {
-                            // if the player is moving towards the solid object, don't move
-                            if direction.y < 0.0 {
-                                direction.y = 0.0;
-                            }
-                        }
-                    }
-                }
+    for (entity, mut transform, mut animation, player_collider, mut sprite, mut stamina, dodge_roll, shield, stats) in query.iter_mut() {
+        // the move actions pick items while the inventory is open
+        let direction = if inventory_ui.open { Vec2::ZERO } else { actions.movement() };
+        let blocking = shield.is_some_and(|shield| shield.raised);
+        let velocity = if let Some(mut dodge_roll) = dodge_roll {
+            // no steering mid roll
+            if dodge_roll.timer.tick(time.delta()).finished() {
+                commands.entity(entity).remove::<DodgeRoll>();
             }
-        }
-
-        if direction.length() > 0.0 {
-            direction = direction.normalize();
-            transform.translation += direction * 100.0 * time.delta_seconds();
+            animation.0 = PlayerAnimationStates::Jump;
+            dodge_roll.direction * DODGE_SPEED
+        } else if blocking {
+            // shuffle along behind the shield, keeping it facing the same way
+            animation.0 = PlayerAnimationStates::Duck;
+            direction * BLOCK_SPEED
+        } else if !inventory_ui.open && actions.just_pressed(InputAction::Dodge) && stamina.try_spend(DODGE_COST) {
+            let roll_direction = if direction.length() > 0.0 {
+                direction.normalize()
+            } else if sprite.flip_x {
+                Vec2::NEG_X
+            } else {
+                Vec2::X
+            };
+            commands
+                .entity(entity)
+                .insert(DodgeRoll {
+                    direction: roll_direction,
+                    timer: Timer::from_seconds(DODGE_TIME, false),
+                })
+                .insert(Invulnerable(Timer::from_seconds(DODGE_TIME, false)));
+            animation.0 = PlayerAnimationStates::Jump;
+            roll_direction * DODGE_SPEED
+        } else if direction.length() > 0.0 && actions.pressed(InputAction::Run) && !stamina.is_empty() {
+            stamina.drain(RUN_COST * time.delta_seconds());
+            animation.0 = PlayerAnimationStates::Run;
+            direction * RUN_SPEED
+        } else if direction.length() > 0.0 {
             animation.0 = PlayerAnimationStates::Walk;
+            direction * WALK_SPEED
         } else {
             animation.0 = PlayerAnimationStates::Idle;
+            Vec2::ZERO
+        };
+        let velocity = velocity * stats.map_or(1.0, Stats::speed_multiplier);
+
+        // flip the sprite, unless it's behind a shield
+        if !blocking {
+            if velocity.x < 0.0 {
+                sprite.flip_x = true;
+            } else if velocity.x > 0.0 {
+                sprite.flip_x = false;
+            }
+        }
+
+        // everything near the player that the player can't walk through
+        let (min, max) = player_collider.bounds(&transform);
+        let near = solid_grid.near(min, max, velocity * time.delta_seconds());
+        let solids = blocking_solids(player_collider, near.filter_map(|other| entities.get(other).ok()));
+
+        // if something ended up inside the player (spawned there, or it moved into us), push the player out first
+        transform.translation += depenetrate(min, max, &solids).extend(0.0);
+
+        if velocity.length() > 0.0 {
+            // move each axis separately, so blocking one still lets the player slide along the other
+            let (min, max) = player_collider.bounds(&transform);
+            let delta = sweep_aabb(min, max, velocity * time.delta_seconds(), &solids);
+            transform.translation += delta.extend(0.0);
+        }
+    }
+}
+
+/*
+Give back stamina once it hasn't been spent for a moment
+ */
+pub fn regenerate_stamina(
+    time: Res<Time>,
+    mut query: Query<&mut Stamina>,
+) {
+    for mut stamina in query.iter_mut() {
+        stamina.since_spent += time.delta_seconds();
+        if stamina.since_spent >= stamina.regen_delay {
+            stamina.current = (stamina.current + stamina.regen * time.delta_seconds()).min(stamina.max);
         }
     }
-}
\ No newline at end of file
+}
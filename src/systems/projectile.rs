@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+
+use crate::components::animation::Animator;
+use crate::components::projectile::{Exploded, Impacted, Projectile, ProjectileKind};
+use crate::components::{as_collider, BoxCollider, CircleCollider, ColliderType, Collision, CollisionLayer, FloorEntity, Health, LayerMask};
+use crate::events::{HitEvent, StatusEffectEvent, TriggerEntered};
+use crate::systems::dungeon_gen::Dungeon;
+use crate::MyAssets;
+
+// the projectile art is drawn a lot bigger than the 32x32 tiles
+const PROJECTILE_SCALE: f32 = 0.6;
+
+/*
+Fire a projectile from position towards target
+Bombs are lobbed, they come down where target was instead of flying on past it
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    assets: &MyAssets,
+    kind: ProjectileKind,
+    owner: Entity,
+    position: Vec2,
+    target: Vec2,
+    targets: LayerMask,
+    damage_multiplier: f32,
+) -> Entity {
+    let direction = (target - position).normalize_or_zero();
+    let lifetime = match kind {
+        ProjectileKind::Bomb => (target.distance(position) / kind.speed()).min(kind.lifetime()),
+        _ => kind.lifetime(),
+    };
+    let size = kind.size();
+    let atlas = assets.atlas(kind.animation_set()).cloned().unwrap_or_default();
+
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            texture_atlas: atlas,
+            sprite: TextureAtlasSprite {
+                flip_x: direction.x < 0.0,
+                ..Default::default()
+            },
+            // above the enemies
+            transform: Transform::from_translation(position.extend(2.0)).with_scale(Vec3::splat(PROJECTILE_SCALE)),
+            ..Default::default()
+        })
+        .insert(FloorEntity)
+        .insert(Projectile {
+            kind,
+            owner,
+            velocity: direction * kind.speed(),
+            targets,
+            damage_multiplier,
+            lifetime: Timer::from_seconds(lifetime, false),
+        })
+        .insert(Animator::new(kind.animation_set(), "fly"))
+        .insert(BoxCollider {
+            width: size.x,
+            height: size.y,
+            membership: LayerMask::from_layers(&[CollisionLayer::Projectile]),
+            collision_mask: targets,
+            offset: Vec2::ZERO,
+            scale: Vec2::ONE,
+            collider_type: ColliderType::Trigger,
+        })
+        .insert(Collision {
+            collisions: Vec::new(),
+        })
+        .id()
+}
+
+/*
+Fly in a straight line, stopping at the first wall of the dungeon or when time runs out
+ */
+pub fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    dungeon: Res<Dungeon>,
+    mut query: Query<(Entity, &mut Transform, &mut Projectile), Without<Impacted>>,
+) {
+    for (entity, mut transform, mut projectile) in query.iter_mut() {
+        let next = transform.translation.truncate() + projectile.velocity * time.delta_seconds();
+        let tile = Dungeon::world_to_tile(next);
+
+        if dungeon.is_wall(tile.x, tile.y) {
+            commands.entity(entity).insert(Impacted);
+            continue;
+        }
+
+        transform.translation = next.extend(transform.translation.z);
+        if projectile.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).insert(Impacted);
+        }
+    }
+}
+
+/*
+Projectiles that do damage on contact hurt the first target they touch and stop there
+ */
+pub fn projectile_hits(
+    mut commands: Commands,
+    mut trigger_entered: EventReader<TriggerEntered>,
+    projectiles: Query<(&Projectile, &Transform), Without<Impacted>>,
+    targets: Query<(), With<Health>>,
+    mut hits: EventWriter<HitEvent>,
+    mut effects: EventWriter<StatusEffectEvent>,
+    mut impacted: Local<Vec<Entity>>,
+) {
+    impacted.clear();
+
+    for event in trigger_entered.iter() {
+        let (projectile, _) = match projectiles.get(event.trigger) {
+            Ok(projectile) => projectile,
+            Err(_) => continue,
+        };
+        // only one target per projectile, even if it touched several this frame
+        if impacted.contains(&event.trigger) || event.other == projectile.owner || targets.get(event.other).is_err() {
+            continue;
+        }
+
+        if let Some((damage, knockback)) = projectile.kind.damage() {
+            hits.send(HitEvent {
+                target: event.other,
+                source: projectile.owner,
+                damage: damage * projectile.damage_multiplier,
+                knockback: projectile.velocity.normalize_or_zero() * knockback,
+            });
+            if let Some(effect) = projectile.kind.effect() {
+                effects.send(StatusEffectEvent {
+                    target: event.other,
+                    source: projectile.owner,
+                    effect,
+                });
+            }
+            commands.entity(event.trigger).insert(Impacted);
+            impacted.push(event.trigger);
+        }
+    }
+}
+
+/*
+A projectile that just stopped stops taking part in collisions and plays its burst,
+bombs start their fuse instead
+The lifetime starts over as however long it lingers, see finish_projectiles
+ */
+pub fn impact_projectiles(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Projectile, &mut Animator), Added<Impacted>>,
+) {
+    for (entity, mut projectile, mut animator) in query.iter_mut() {
+        projectile.velocity = Vec2::ZERO;
+        projectile.lifetime = Timer::from_seconds(projectile.kind.linger(), false);
+        commands
+            .entity(entity)
+            .remove::<BoxCollider>()
+            .remove::<Collision>();
+
+        animator.play(if projectile.kind.explosion().is_some() { "fuse" } else { "burst" });
+    }
+}
+
+/*
+Once a bomb's fuse burns down it hurts everything in range, pushing it away from the blast,
+and once the burst or blast is over the projectile is gone
+This runs off the lifetime, the animations only show it, so bombs still go off without them
+ */
+#[allow(clippy::type_complexity)]
+pub fn finish_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectiles: Query<(Entity, &mut Projectile, &Transform, &mut Animator, Option<&Exploded>), With<Impacted>>,
+    targets: Query<(Entity, &Transform, Option<&BoxCollider>, Option<&CircleCollider>), With<Health>>,
+    mut hits: EventWriter<HitEvent>,
+) {
+    for (entity, mut projectile, transform, mut animator, exploded) in projectiles.iter_mut() {
+        if !projectile.lifetime.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let explosion = match (projectile.kind.explosion(), exploded) {
+            (Some(explosion), None) => explosion,
+            _ => {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+        };
+
+        let center = transform.translation.truncate();
+        for (target, target_transform, box_collider, circle_collider) in targets.iter() {
+            let in_targets = as_collider(box_collider, circle_collider)
+                .is_some_and(|collider| projectile.targets.intersects(collider.membership()));
+            let offset = target_transform.translation.truncate() - center;
+            if target == projectile.owner || !in_targets || offset.length() > explosion.radius {
+                continue;
+            }
+
+            hits.send(HitEvent {
+                target,
+                source: projectile.owner,
+                damage: explosion.damage * projectile.damage_multiplier,
+                knockback: offset.normalize_or_zero() * explosion.knockback,
+            });
+        }
+
+        projectile.lifetime = Timer::from_seconds(explosion.duration, false);
+        commands.entity(entity).insert(Exploded);
+        animator.play("explode");
+    }
+}
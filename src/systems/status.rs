@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::components::stats::Stats;
+use crate::components::status::{ActiveEffect, EffectKind, StatusEffects};
+use crate::components::{Health, Invulnerable};
+use crate::events::{DamageEvent, DeathEvent, StatusEffectEvent};
+
+/*
+Put on or take off what an effect changes while it lasts
+Stat points go into Stats, entities without them just don't get the stat part
+ */
+fn set_modifiers(kind: &EffectKind, stats: Option<&mut Stats>, health: &mut Health, on: bool) {
+    let points = match *kind {
+        EffectKind::Strength(points) => Stats {
+            strength: points,
+            ..default()
+        },
+        EffectKind::Dexterity(points) => Stats {
+            dexterity: points,
+            ..default()
+        },
+        EffectKind::Armor(armor) => {
+            health.armor += if on { armor } else { -armor };
+            return;
+        }
+        _ => return,
+    };
+
+    if let Some(stats) = stats {
+        if on {
+            *stats += points;
+        } else {
+            *stats -= points;
+        }
+    }
+}
+
+/*
+Put every effect that was sent on its target
+An effect of a kind the target is already under replaces the old one instead of stacking with it,
+so drinking a second potion starts the timer over
+ */
+#[allow(clippy::type_complexity)]
+pub fn apply_status_effects(
+    mut events: EventReader<StatusEffectEvent>,
+    mut query: Query<(&mut StatusEffects, &mut Health, Option<&mut Stats>, Option<&Invulnerable>)>,
+) {
+    for event in events.iter() {
+        let (mut effects, mut health, mut stats, invulnerable) = match query.get_mut(event.target) {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        if health.is_dead() || (event.effect.kind.is_harmful() && invulnerable.is_some()) {
+            continue;
+        }
+
+        if let Some(index) = effects.0.iter().position(|active| active.effect.kind.same_kind(&event.effect.kind)) {
+            let old = effects.0.remove(index);
+            set_modifiers(&old.effect.kind, stats.as_deref_mut(), &mut health, false);
+        }
+        set_modifiers(&event.effect.kind, stats.as_deref_mut(), &mut health, true);
+        debug!("{:?} is under {} for {}s", event.target, event.effect.kind.name(), event.effect.duration);
+        effects.0.push(ActiveEffect::new(event.effect, event.source));
+    }
+}
+
+/*
+Run down every effect, doing what it does over time every tick, and take it off once it runs out
+Poison goes straight to the hp, past armor and shields, and counts as a kill for whoever put it on
+ */
+pub fn tick_status_effects(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut StatusEffects, &mut Health, Option<&mut Stats>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+) {
+    for (entity, mut effects, mut health, mut stats) in query.iter_mut() {
+        if effects.0.is_empty() || health.is_dead() {
+            continue;
+        }
+
+        for active in effects.0.iter_mut() {
+            active.remaining.tick(time.delta());
+            let ticks = active.tick.tick(time.delta()).times_finished_this_tick();
+
+            for _ in 0..ticks {
+                match active.effect.kind {
+                    EffectKind::Regeneration(amount) if !health.is_dead() => {
+                        health.hp = (health.hp + amount).min(health.max_hp);
+                    }
+                    EffectKind::Poison(amount) if !health.is_dead() => {
+                        let amount = amount.min(health.hp);
+                        health.hp -= amount;
+                        damage_events.send(DamageEvent {
+                            target: entity,
+                            source: active.source,
+                            amount,
+                            blocked: false,
+                        });
+                        if health.is_dead() {
+                            death_events.send(DeathEvent {
+                                entity,
+                                killer: active.source,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        effects.0.retain(|active| {
+            if !active.remaining.finished() {
+                return true;
+            }
+            set_modifiers(&active.effect.kind, stats.as_deref_mut(), &mut health, false);
+            false
+        });
+    }
+}
@@ -1,13 +1,17 @@
 use std::cmp::min;
 use bevy::prelude::*;
-use crate::{Collision, MyAssets, MyStates};
+use crate::{BoxCollider, MyAssets, MyStates};
+use crate::components::{ColliderType, CollisionLayer, FloorEntity, Health, LayerMask, Stairs, Wall, WallBundle};
+use crate::components::player::PlayerAnimationState;
+use crate::resources::input::{ActionInput, InputAction};
+use crate::resources::inventory::InventoryUi;
 use noise::{Fbm, Perlin};
 use bevy_ecs_tilemap::prelude::*;
 use bevy::{math::Vec3Swizzles, utils::HashSet};
 use noise::utils::{NoiseMapBuilder, PlaneMapBuilder};
 use crate::resources::{CHUNK_SIZE, RENDER_CHUNK_SIZE, RENDER_SIZE, RenderTimer, TILE_SIZE, WORLD_SIZE, WorldMap};
 
-use super::dungeon_gen::Dungeon;
+use super::dungeon_gen::{Dungeon, STAIRS_SIZE, TILEMAP_COLUMNS, TILE_STAIRS};
 
 #[derive(Default, Debug)]
 pub struct ChunkManager {
@@ -21,6 +25,7 @@ fn tile_to_world_pos(tile_pos: TilePos, chunk_pos: IVec2) -> Vec2 {
     Vec2::new(x, y)
 }
 
+#[allow(dead_code)]
 pub fn get_center_of_world() -> Vec2 {
     let x = (((WORLD_SIZE.x as i32) * TILE_SIZE.x as i32) / 2) as f32;
     let y = (((WORLD_SIZE.y as i32) * TILE_SIZE.y as i32) / 2) as f32;
@@ -30,7 +35,7 @@ pub fn get_center_of_world() -> Vec2 {
 
 fn spawn_chunk(commands: &mut Commands, assets: &Res<MyAssets>, chunk_pos: IVec2, world_map: &Res<Dungeon>) {
 
-    let tilemap_entity = commands.spawn().id();
+    let tilemap_entity = commands.spawn().insert(FloorEntity).id();
     let mut tile_storage = TileStorage::empty(CHUNK_SIZE.into());
     // Spawn the elements of the tilemap.
     for x in 0..CHUNK_SIZE.x {
@@ -40,16 +45,17 @@ fn spawn_chunk(commands: &mut Commands, assets: &Res<MyAssets>, chunk_pos: IVec2
             let tile_world_pos = tile_to_world_pos(tile_pos, chunk_pos);
 
             // dont spawn out of bounds tiles
-            if tile_world_pos.x < 0.0 || tile_world_pos.y < 0.0 || tile_world_pos.x >= WORLD_SIZE.x as f32 || tile_world_pos.y >= WORLD_SIZE.y as f32 {
-                continue;
-            }
+            let tile = match world_map.tile(tile_world_pos.x as i32, tile_world_pos.y as i32) {
+                Some(tile) => tile,
+                None => continue,
+            };
 
             let tile_entity = commands
                 .spawn()
                 .insert_bundle(TileBundle {
                     position: tile_pos,
                     tilemap_id: TilemapId(tilemap_entity),
-                    texture: TileTexture(world_map.tile_map[tile_world_pos.x as usize][tile_world_pos.y as usize]),
+                    texture: TileTexture(tile),
                     ..Default::default()
                 })
                 .id();
@@ -63,7 +69,7 @@ fn spawn_chunk(commands: &mut Commands, assets: &Res<MyAssets>, chunk_pos: IVec2
         chunk_pos.y as f32 * CHUNK_SIZE.y as f32 * TILE_SIZE.y,
         0.0,
     ));
-    let texture_handle: Handle<Image> = assets.grasses_raw.clone();
+    let texture_handle: Handle<Image> = assets.base_tilemap.clone();
     commands
         .entity(tilemap_entity)
         .insert_bundle(TilemapBundle {
@@ -77,6 +83,114 @@ fn spawn_chunk(commands: &mut Commands, assets: &Res<MyAssets>, chunk_pos: IVec2
         });
 }
 
+/*
+Spawn the static colliders for the dungeon walls
+Each merged rectangle of wall tiles becomes one collider centered over the tiles it covers
+ */
+pub fn spawn_wall_colliders(
+    mut commands: Commands,
+    dungeon: Res<Dungeon>,
+) {
+    let rects = dungeon.wall_rects();
+
+    for rect in &rects {
+        // tiles are centered on their grid position, so the rect starts half a tile before it
+        let center = Vec2::new(
+            (rect.x as f32 + rect.w as f32 / 2.0 - 0.5) * TILE_SIZE.x,
+            (rect.y as f32 + rect.h as f32 / 2.0 - 0.5) * TILE_SIZE.y,
+        );
+
+        commands.spawn_bundle(WallBundle {
+            wall: Wall,
+            collider: BoxCollider {
+                width: rect.w as f32 * TILE_SIZE.x,
+                height: rect.h as f32 * TILE_SIZE.y,
+                membership: LayerMask::from_layers(&[CollisionLayer::Wall]),
+                collision_mask: LayerMask::ALL,
+                offset: Vec2::ZERO,
+                scale: Vec2::ONE,
+                collider_type: ColliderType::Solid,
+            },
+            transform: TransformBundle::from_transform(Transform::from_translation(center.extend(0.0))),
+        })
+        .insert(FloorEntity);
+    }
+
+    debug!("Spawned {} wall colliders", rects.len());
+}
+
+/*
+Draw the stairs over the floor at Dungeon::stairs_point, one sprite for each tile of the staircase
+Interacting on them goes down to the next floor, see descend_stairs
+ */
+pub fn spawn_stairs(
+    mut commands: Commands,
+    assets: Res<MyAssets>,
+    dungeon: Res<Dungeon>,
+) {
+    // above the tilemap, under the pickups
+    let center = dungeon.stairs_point().extend(0.25);
+
+    commands
+        .spawn_bundle(SpatialBundle::from_transform(Transform::from_translation(center)))
+        .insert(Stairs)
+        .insert(FloorEntity)
+        .with_children(|stairs| {
+            for row in 0..STAIRS_SIZE.y {
+                for column in 0..STAIRS_SIZE.x {
+                    // the sheet goes down, the world goes up
+                    let offset = Vec2::new(
+                        (column as f32 - (STAIRS_SIZE.x - 1) as f32 / 2.0) * TILE_SIZE.x,
+                        ((STAIRS_SIZE.y - 1) as f32 / 2.0 - row as f32) * TILE_SIZE.y,
+                    );
+                    stairs.spawn_bundle(SpriteSheetBundle {
+                        texture_atlas: assets.dungeon_tiles.clone(),
+                        sprite: TextureAtlasSprite::new((TILE_STAIRS + row * TILEMAP_COLUMNS + column) as usize),
+                        transform: Transform::from_translation(offset.extend(0.0)),
+                        ..Default::default()
+                    });
+                }
+            }
+        });
+}
+
+/*
+Interact while standing on the stairs goes down to the next floor
+Leaving the game clears this floor and generating makes the next one, the player comes along
+ */
+pub fn descend_stairs(
+    mut actions: ResMut<ActionInput>,
+    inventory_ui: Res<InventoryUi>,
+    mut dungeon: ResMut<Dungeon>,
+    mut app_state: ResMut<State<MyStates>>,
+    player: Query<(&Transform, &Health), With<PlayerAnimationState>>,
+    stairs: Query<&Transform, With<Stairs>>,
+) {
+    // interact is the panel's while it's open, the same as attacking in player_attack
+    if inventory_ui.open || !actions.just_pressed(InputAction::Interact) {
+        return;
+    }
+    let (player, health) = match player.get_single() {
+        Ok(player) => player,
+        Err(_) => return,
+    };
+    let reach = STAIRS_SIZE.as_vec2() * Vec2::new(TILE_SIZE.x, TILE_SIZE.y) / 2.0;
+    let on_stairs = stairs
+        .iter()
+        .any(|stairs| (player.translation.truncate() - stairs.translation.truncate()).abs().cmple(reach).all());
+    if health.is_dead() || !on_stairs {
+        return;
+    }
+
+    actions.consume(InputAction::Interact);
+    dungeon.descend();
+    info!("Going down to floor {}", dungeon.floor);
+    app_state
+        .set(MyStates::DungeonGeneration)
+        .unwrap_or_else(|e| error!("Failed to go down the stairs: {:?}", e));
+}
+
+#[allow(dead_code)]
 pub fn generate_world(
     mut app_state: ResMut<State<MyStates>>,
     mut world: ResMut<WorldMap>,
@@ -168,10 +282,11 @@ pub fn spawn_chunks_around_camera(
 
 
 
+#[allow(clippy::unnecessary_cast)]
 pub fn despawn_outofrange_chunks(
     mut commands: Commands,
     camera_query: Query<&Transform, With<Camera>>,
-    chunks_query: Query<(Entity, &Transform), Without<Collision>>,
+    chunks_query: Query<(Entity, &Transform), With<TileStorage>>,
     mut chunk_manager: ResMut<ChunkManager>,
     time: Res<Time>,
     mut timer: ResMut<RenderTimer>